@@ -0,0 +1,35 @@
+use std::future::Future;
+
+use kv_storage::{overlay, KvStorage};
+use serde::Serialize;
+use warp_erc1155::action::{SimulationResult, StateChange};
+
+/// Runs `execution` on top of a KV overlay that is discarded afterwards, and reports what it would
+/// change in `parameters` and in the KV storage. `execution` returns the new parameters along with
+/// its response, if any.
+///
+/// The writes to other contracts are left to the caller, they aren't part of the result.
+pub async fn simulate<K: KvStorage, P: Serialize, ERROR, RESPONSE>(
+    parameters: &P,
+    execution: impl Future<Output = Result<(P, Option<RESPONSE>), ERROR>>,
+) -> SimulationResult<ERROR, RESPONSE> {
+    overlay::start();
+
+    let result = execution.await;
+    let kv_changes = overlay::finish::<K>().await;
+
+    match result {
+        Ok((new_parameters, response)) => {
+            let mut changes = StateChange::from_parameters(parameters, &new_parameters);
+            changes.extend(kv_changes.into_iter().map(StateChange::from));
+
+            SimulationResult {
+                error: None,
+                changes,
+                foreign_writes: vec![],
+                response,
+            }
+        }
+        Err(error) => SimulationResult::failed(error),
+    }
+}
@@ -0,0 +1,45 @@
+//! Checks of the initial states given to `Initialize` that every contract makes
+
+/// Whether `segment` can be part of a KV key, whose segments are separated by `.`
+pub fn is_valid_key_segment(segment: &str) -> bool {
+    !segment.is_empty() && !segment.contains('.')
+}
+
+/// Lists the entries that appear more than once in `entries`, each of them once and in the order
+/// of their first repetition.
+pub fn find_duplicates(entries: &[String]) -> Vec<&String> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(i, entry)| entries[..*i].iter().filter(|e| e == entry).count() == 1)
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Sorts the entries of a map by key, which keeps the order of the reported violations
+/// deterministic.
+pub fn sorted<'a, T>(map: impl IntoIterator<Item = (&'a String, T)>) -> Vec<(&'a String, T)> {
+    let mut entries: Vec<_> = map.into_iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_and_dotted_segments() {
+        assert!(is_valid_key_segment("NFT-0"));
+        assert!(!is_valid_key_segment(""));
+        assert!(!is_valid_key_segment("NFT.0"));
+    }
+
+    #[test]
+    fn reports_each_duplicate_once() {
+        let entries = ["a", "b", "a", "c", "b", "a"].map(String::from);
+
+        assert_eq!(find_duplicates(&entries), vec!["a", "b"]);
+        assert!(find_duplicates(&entries[..2]).is_empty());
+    }
+}
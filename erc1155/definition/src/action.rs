@@ -1,8 +1,13 @@
+use kv_storage::overlay::KvChange;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::error::ContractError;
-use crate::state::{Balance, Parameters, Settings, Token};
+use crate::state::{
+    Balance, Parameters, ProxyPermission, ProxyPermissions, Role, Settings, Snapshot, Token,
+    TokenIdPolicy,
+};
 
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -14,6 +19,14 @@ pub struct AsDirectCaller {
     pub action: Box<Action>,
 }
 
+/// Handles `action` as if it was the interaction's input, without persisting anything, and reports
+/// what it would change
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulate {
+    pub action: Box<Action>,
+}
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct BalanceOf {
@@ -21,6 +34,23 @@ pub struct BalanceOf {
     pub target: String,
 }
 
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceOfAt {
+    pub token_id: Option<String>,
+    pub target: String,
+    pub snapshot_id: u32,
+}
+
+/// Records the current balances, which can then be read with `BalanceOfAt`
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TakeSnapshot;
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSnapshots;
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct GetToken {
@@ -31,6 +61,16 @@ pub struct GetToken {
 #[serde(rename_all = "camelCase")]
 pub struct GetAllTokens;
 
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetHolders {
+    pub token_id: Option<String>,
+    /// Only holders whose address comes after this one are returned
+    pub after: Option<String>,
+    /// Defaults to 100, at most 1000
+    pub limit: Option<u32>,
+}
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ReadSettings;
@@ -44,23 +84,158 @@ pub struct Transfer {
     pub qty: Balance,
 }
 
+/// Transfers tokens from a single owner to many recipients
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Airdrop {
+    pub token_id: Option<String>,
+    pub from: Option<String>,
+    /// recipient -> quantity
+    pub recipients: Vec<(String, Balance)>,
+    /// Whether invalid recipients are skipped or make the whole airdrop fail
+    #[serde(default)]
+    pub skip_invalid: bool,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirdropResult {
+    pub recipient: String,
+    pub qty: Balance,
+    /// Why the recipient was skipped, if it was
+    pub error: Option<ContractError>,
+}
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Configure {
+    /// Legacy list, whose added and removed addresses are granted and revoked every role
     pub super_operators: Option<Vec<String>>,
+    /// Legacy list, whose added and removed addresses are granted and revoked the minter, burner
+    /// and pauser roles
     pub operators: Option<Vec<String>>,
     pub proxies: Option<Vec<String>>,
     pub paused: Option<bool>,
     pub can_evolve: Option<bool>,
     pub allow_free_transfer: Option<bool>,
+    pub token_id_policy: Option<TokenIdPolicy>,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantRole {
+    pub role: Role,
+    pub account: String,
 }
 
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeRole {
+    pub role: Role,
+    pub account: String,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenounceRole {
+    pub role: Role,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HasRole {
+    pub role: Role,
+    pub account: String,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProxyPermissions {
+    pub proxy: String,
+    pub permissions: Vec<ProxyPermission>,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProxies;
+
+/// Subscribes the caller, which must be a contract, to `OnReceived` notifications
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterReceiver;
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnregisterReceiver;
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IsReceiver {
+    pub address: String,
+}
+
+/// Sent to registered receivers when tokens are transferred to them. Returning an error rolls the
+/// transfer back.
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OnReceived {
+    pub operator: String,
+    /// Empty for minted tokens
+    pub from: String,
+    pub token_id: String,
+    pub qty: Balance,
+}
+
+/// Actions that registered receivers must handle
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "function")]
+pub enum ReceiverAction {
+    OnReceived(OnReceived),
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Freeze {
+    pub account: String,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Unfreeze {
+    pub account: String,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFrozenAccounts;
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseToken {
+    pub token_id: String,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnpauseToken {
+    pub token_id: String,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPausedTokens;
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Mint {
     pub base_id: Option<String>,
     pub prefix: Option<String>,
     pub qty: Balance,
+    /// Only applies when the token is created, later mints must leave it empty or equal
+    pub max_supply: Option<Balance>,
+    /// Account credited with the minted tokens, defaults to the caller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
 }
 
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -68,7 +243,11 @@ pub struct Mint {
 pub struct Burn {
     pub token_id: Option<String>,
     pub qty: Balance,
+    /// Defaults to the caller, who must otherwise be approved by the owner or be a burner
     pub owner: Option<String>,
+    /// Required from burners burning tokens they aren't approved for, the reason is then recorded
+    /// in the interaction
+    pub reason: Option<String>,
 }
 
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
@@ -78,6 +257,41 @@ pub struct SetApprovalForAll {
     pub approved: bool,
 }
 
+/// Approval signed by `owner` off-chain, that anyone can submit on their behalf
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Permit {
+    pub owner: String,
+    pub operator: String,
+    pub approved: bool,
+    /// Must be equal to the owner's current nonce, see `GetPermitNonce`
+    pub nonce: u32,
+    /// Last block height at which the permit can be used
+    pub deadline: u32,
+    /// Modulus (`n`) of the owner's RSA key, base64url encoded. Arweave addresses are derived from
+    /// it, so it can't be recovered from the address alone.
+    pub public_key: String,
+    /// RSA-PSS SHA-256 signature of `Permit::message`, base64url encoded
+    pub signature: String,
+}
+
+impl Permit {
+    /// Canonical message signed by the owner. The contract id prevents a permit from being
+    /// replayed on another contract.
+    pub fn message(&self, contract_id: &str) -> String {
+        format!(
+            "permit:{}:{}:{}:{}:{}:{}",
+            contract_id, self.owner, self.operator, self.approved, self.nonce, self.deadline
+        )
+    }
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPermitNonce {
+    pub owner: String,
+}
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct IsApprovedForAll {
@@ -91,6 +305,37 @@ pub struct Batch {
     pub actions: Vec<Action>,
 }
 
+#[derive(JsonSchema, Clone, Copy, Debug, Serialize, Deserialize, Hash, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchMode {
+    /// The whole batch fails as soon as one of its actions fails
+    #[default]
+    Atomic,
+    /// Failing actions are rolled back and reported, the others still apply. Actions failing after
+    /// writing to other contracts fail the whole batch, as these writes can't be rolled back on
+    /// their own.
+    BestEffort,
+}
+
+/// Unlike `Batch`, reads and writes can be mixed. The actions can't change the contract's
+/// parameters (e.g. `Evolve`) as the results are returned like a read's.
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchV2 {
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
+/// Outcome of one of a `BatchV2`'s actions
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "camelCase")]
+pub enum BatchActionResult<ERROR, RESPONSE> {
+    Read(RESPONSE),
+    Write,
+    Error(ERROR),
+}
+
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Evolve {
@@ -102,18 +347,128 @@ pub struct Evolve {
 pub enum Action {
     Initialize(Initialize),
     AsDirectCaller(AsDirectCaller),
+    Simulate(Simulate),
     BalanceOf(BalanceOf),
+    BalanceOfAt(BalanceOfAt),
+    Snapshot(TakeSnapshot),
+    GetSnapshots(GetSnapshots),
     GetToken(GetToken),
     GetAllTokens(GetAllTokens),
+    GetHolders(GetHolders),
     ReadSettings(ReadSettings),
     Transfer(Transfer),
+    Airdrop(Airdrop),
     Configure(Configure),
+    GrantRole(GrantRole),
+    RevokeRole(RevokeRole),
+    RenounceRole(RenounceRole),
+    HasRole(HasRole),
+    SetProxyPermissions(SetProxyPermissions),
+    GetProxies(GetProxies),
+    RegisterReceiver(RegisterReceiver),
+    UnregisterReceiver(UnregisterReceiver),
+    IsReceiver(IsReceiver),
+    Freeze(Freeze),
+    Unfreeze(Unfreeze),
+    GetFrozenAccounts(GetFrozenAccounts),
+    PauseToken(PauseToken),
+    UnpauseToken(UnpauseToken),
+    GetPausedTokens(GetPausedTokens),
     SetApprovalForAll(SetApprovalForAll),
     IsApprovedForAll(IsApprovedForAll),
+    Permit(Permit),
+    GetPermitNonce(GetPermitNonce),
     Evolve(Evolve),
     Mint(Mint),
     Burn(Burn),
     Batch(Batch),
+    BatchV2(BatchV2),
+}
+
+/// An entry of the contract's state that a simulated action would modify, `None` meaning that the
+/// entry doesn't exist. KV entries are identified by their path (e.g.
+/// `.tokens.<token id>.balances.<address>`), fields of the contract's parameters by their name.
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChange {
+    pub key: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl StateChange {
+    /// Lists the top-level fields that differ between two versions of the contract's parameters
+    pub fn from_parameters<T: Serialize>(before: &T, after: &T) -> Vec<StateChange> {
+        let (before, mut after) = match (serde_json::to_value(before), serde_json::to_value(after))
+        {
+            (Ok(Value::Object(before)), Ok(Value::Object(after))) => (before, after),
+            _ => return vec![],
+        };
+
+        let mut changes: Vec<StateChange> = before
+            .into_iter()
+            .filter_map(|(key, before)| {
+                let after = after.remove(&key);
+
+                let changed = after.as_ref() != Some(&before);
+
+                changed.then_some(StateChange {
+                    key,
+                    before: Some(before),
+                    after,
+                })
+            })
+            .collect();
+
+        changes.extend(after.into_iter().map(|(key, after)| StateChange {
+            key,
+            before: None,
+            after: Some(after),
+        }));
+
+        changes
+    }
+}
+
+impl From<KvChange> for StateChange {
+    fn from(change: KvChange) -> Self {
+        Self {
+            key: change.key,
+            before: change.before,
+            after: change.after,
+        }
+    }
+}
+
+/// A write to another contract that a simulated action would make
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignWrite {
+    pub contract_id: String,
+    pub input: Value,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResult<ERROR, RESPONSE> {
+    /// Why the action would fail, in which case it wouldn't change anything
+    pub error: Option<ERROR>,
+    pub changes: Vec<StateChange>,
+    /// Writes to other contracts aren't made, they're assumed to succeed
+    pub foreign_writes: Vec<ForeignWrite>,
+    /// What the action would return, if anything
+    pub response: Option<RESPONSE>,
+}
+
+impl<ERROR, RESPONSE> SimulationResult<ERROR, RESPONSE> {
+    pub fn failed(error: ERROR) -> Self {
+        Self {
+            error: Some(error),
+            changes: vec![],
+            foreign_writes: vec![],
+            response: None,
+        }
+    }
 }
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
@@ -124,10 +479,30 @@ pub enum ReadResponse {
         target: String,
     },
 
+    #[serde(rename_all = "camelCase")]
+    BalanceOfAt {
+        balance: Balance,
+        target: String,
+        snapshot_id: u32,
+    },
+
+    GetSnapshots(Vec<Snapshot>),
+
     GetToken((String, Token)),
 
     GetAllTokens(Vec<(String, Token)>),
 
+    #[serde(rename_all = "camelCase")]
+    GetHolders {
+        token_id: String,
+        /// Holders sorted by address
+        holders: Vec<(String, Balance)>,
+        /// Total number of holders of the token
+        holders_count: u32,
+        /// Cursor to pass as `after` to get the next page, if there is one
+        next: Option<String>,
+    },
+
     ReadSettings(Settings),
 
     IsApprovedForAll {
@@ -136,9 +511,40 @@ pub enum ReadResponse {
         operator: String,
     },
 
+    GetPermitNonce {
+        owner: String,
+        nonce: u32,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    HasRole {
+        has_role: bool,
+        role: Role,
+        account: String,
+    },
+
+    GetProxies(ProxyPermissions),
+
+    IsReceiver {
+        address: String,
+        registered: bool,
+    },
+
+    GetFrozenAccounts(Vec<String>),
+
+    GetPausedTokens(Vec<String>),
+
+    Airdrop(Vec<AirdropResult>),
+
+    Simulate(Box<SimulationResult<ContractError, ReadResponse>>),
+
     Batch(Vec<ReadResponse>),
+
+    BatchV2(Vec<BatchActionResult<ContractError, ReadResponse>>),
 }
 
+// `HandlerResult`s are short-lived, boxing their state wouldn't save anything
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum HandlerResult {
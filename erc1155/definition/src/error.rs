@@ -1,11 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(JsonSchema, Serialize, Deserialize, Debug)]
+use crate::state::ProxyPermission;
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "kind", content = "data")]
 pub enum ContractError {
     RuntimeError(String),
     TransferAmountMustBeHigherThanZero,
+    BalanceOverflow,
     TransferFromAndToCannotBeEqual,
     TokenNotFound(String),
     IDontLikeThisContract,
@@ -14,15 +17,63 @@ pub enum ContractError {
     EvolveNotAllowed,
 
     ForbiddenNestedBatch,
+    /// `AsDirectCaller`, `Initialize` and `Permit` can't be batched
+    ForbiddenActionInBatch,
+    ForbiddenNestedSimulation,
     CannotMixeReadAndWrite,
     EmptyBatch,
+    BatchActionFailed {
+        index: u32,
+        error: Box<ContractError>,
+    },
+    CannotChangeParametersInBatch,
 
     UnauthorizedConfiguration,
     UnauthorizedAddress(String),
+    /// Can't hold balances, not being usable as a KV key segment
+    InvalidAddress(String),
+    PermitExpired,
+    InvalidPermitNonce(u32),
+    InvalidPermitSignature,
     UnauthorizedTransfer(String),
+    BurnReasonRequired,
+    UnauthorizedProxyAction(ProxyPermission),
+    ReceiverRejected(String),
+    SnapshotNotFound(u32),
+    /// Pages must hold between 1 and the maximum number of entries
+    InvalidLimit(u32),
+    CannotRemoveLastConfigurator,
     TokenAlreadyExists,
+    InvalidTokenId(String),
+    MaxSupplyExceeded(String),
+    MaxSupplyIsImmutable(String),
 
     ContractIsPaused,
+    TokenIsPaused(String),
+    AccountIsFrozen(String),
     ContractUninitialized,
     ContractAlreadyInitialized,
+    InvalidInitialState(Vec<InitialStateViolation>),
+}
+
+/// Reason for rejecting the initial state given to `Initialize`
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", content = "data")]
+pub enum InitialStateViolation {
+    DefaultTokenNotFound(String),
+    InvalidTokenId(String),
+    /// Empty or containing a `.`, which separates the segments of KV keys
+    InvalidAddress(String),
+    #[serde(rename_all = "camelCase")]
+    ZeroBalance {
+        token_id: String,
+        address: String,
+    },
+    BalanceOverflow(String),
+    MaxSupplyExceeded(String),
+    /// An address listed more than once in `field`
+    DuplicateEntry {
+        field: String,
+        address: String,
+    },
 }
@@ -1,6 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 mod string {
     use std::fmt::Display;
@@ -28,7 +29,7 @@ mod string {
     }
 }
 
-pub type BalancePrecision = u64;
+pub type BalancePrecision = u128;
 
 #[derive(JsonSchema, Serialize, Deserialize, Copy, Clone, Default, Debug, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase", transparent)]
@@ -42,6 +43,14 @@ impl Balance {
     pub fn new(value: BalancePrecision) -> Self {
         Self { value }
     }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.value.checked_add(other.value).map(Self::new)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.value.checked_sub(other.value).map(Self::new)
+    }
 }
 
 pub type Balances = HashMap<String, Balance>;
@@ -58,8 +67,115 @@ pub struct Token {
     pub ticker: String,
     pub tx_id: Option<String>,
     pub balances: HashMap<String, Balance>,
+    /// Maximum quantity of the token that can be in circulation, set when it is first minted
+    #[serde(default)]
+    pub max_supply: Option<Balance>,
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenIdPolicy {
+    /// Characters that a token id may contain besides ASCII letters and digits. The `.` character
+    /// is never allowed, whatever this field says, as it separates the segments of KV keys.
+    pub allowed_symbols: String,
+
+    /// Maximum length of a token id, in bytes
+    pub max_length: u32,
+
+    /// Prefixes (e.g. `SHUFFLE`) under which only configurators can mint new tokens
+    pub reserved_prefixes: Vec<String>,
+}
+
+impl Default for TokenIdPolicy {
+    fn default() -> Self {
+        Self {
+            // Arweave transaction ids, used as default base ids, are base64url encoded
+            allowed_symbols: "-_".to_string(),
+            max_length: 128,
+            reserved_prefixes: Vec::new(),
+        }
+    }
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    /// Can mint tokens and, when `allow_free_transfer` is disabled, transfer them
+    Minter,
+    /// Can burn tokens from any owner, given a reason
+    Burner,
+    /// Can toggle `paused` and `allow_free_transfer`
+    Pauser,
+    /// Can grant and revoke roles and edit the token id policy
+    Configurator,
+    /// Can evolve the contract and toggle `can_evolve`
+    Evolver,
+    /// Can edit the list of proxies
+    ProxyAdmin,
+}
+
+impl Role {
+    pub const ALL: [Role; 6] = [
+        Role::Minter,
+        Role::Burner,
+        Role::Pauser,
+        Role::Configurator,
+        Role::Evolver,
+        Role::ProxyAdmin,
+    ];
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::Minter => "minter",
+            Role::Burner => "burner",
+            Role::Pauser => "pauser",
+            Role::Configurator => "configurator",
+            Role::Evolver => "evolver",
+            Role::ProxyAdmin => "proxyAdmin",
+        };
+
+        f.write_str(name)
+    }
 }
 
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: u32,
+    pub block_height: u32,
+}
+
+/// role -> addresses
+pub type Roles = HashMap<Role, Vec<String>>;
+
+/// Kinds of actions that a proxy can perform on behalf of the user who signed the transaction
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProxyPermission {
+    Transfer,
+    Mint,
+    Burn,
+    /// `SetApprovalForAll`
+    Approve,
+    /// Every other write action: configuration, roles, freezes, token pauses and evolutions
+    Administer,
+}
+
+impl ProxyPermission {
+    pub const ALL: [ProxyPermission; 5] = [
+        ProxyPermission::Transfer,
+        ProxyPermission::Mint,
+        ProxyPermission::Burn,
+        ProxyPermission::Approve,
+        ProxyPermission::Administer,
+    ];
+}
+
+/// proxy -> permissions
+pub type ProxyPermissions = HashMap<String, Vec<ProxyPermission>>;
+
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
@@ -67,12 +183,28 @@ pub struct Settings {
 
     pub paused: bool,
 
+    /// Members of each role. Roles missing from an initial state are derived from
+    /// `super_operators` and `operators`.
+    #[serde(default)]
+    pub roles: Roles,
+
+    /// NOTE: Superseded by `roles`. Super-operators are given every role.
+    #[serde(default)]
     pub super_operators: Vec<String>,
+    /// NOTE: Superseded by `roles`. Operators are given the minter, burner and pauser roles.
+    #[serde(default)]
     pub operators: Vec<String>,
 
     pub proxies: Vec<String>,
 
+    /// Proxies missing from this map are given no permission
+    #[serde(default)]
+    pub proxy_permissions: ProxyPermissions,
+
     pub allow_free_transfer: bool,
+
+    #[serde(default)]
+    pub token_id_policy: TokenIdPolicy,
 }
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
@@ -96,3 +228,29 @@ pub struct Parameters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub evolve: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balances_are_strings_in_json() {
+        let balance: Balance = serde_json::from_str("\"1000\"").unwrap();
+        assert_eq!(balance, Balance::new(1000));
+
+        let max = Balance::new(u128::MAX);
+        let json = serde_json::to_string(&max).unwrap();
+        assert_eq!(json, format!("\"{}\"", u128::MAX));
+        assert_eq!(serde_json::from_str::<Balance>(&json).unwrap(), max);
+    }
+
+    #[test]
+    fn balance_operations_are_checked() {
+        assert_eq!(Balance::new(u128::MAX).checked_add(Balance::new(1)), None);
+        assert_eq!(Balance::new(0).checked_sub(Balance::new(1)), None);
+        assert_eq!(
+            Balance::new(1).checked_add(Balance::new(2)),
+            Some(Balance::new(3))
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+
+use warp_common::validation::is_valid_key_segment;
+use warp_erc1155::action::{
+    ActionResult, Airdrop, AirdropResult, HandlerResult, OnReceived, ReadResponse,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::Parameters;
+
+use crate::{
+    actions::{
+        pause_token::is_token_paused, receivers::notify_receiver, snapshot::snapshot_balance,
+        transfer::check_can_transfer_from, AsyncActionable,
+    },
+    state::{Balance, State, SubpathToken},
+};
+
+async fn add_to_balance(
+    token: &SubpathToken,
+    token_id: &str,
+    account: &str,
+    qty: Balance,
+) -> Result<(), ContractError> {
+    let balance = token.balances(account).peek().await.unwrap_or_default();
+    let new_balance = balance
+        .checked_add(qty)
+        .ok_or(ContractError::BalanceOverflow)?;
+
+    snapshot_balance(token_id, account, balance).await;
+    token.balances(account).set(&new_balance).await;
+
+    Ok(())
+}
+
+async fn remove_from_balance(
+    token: &SubpathToken,
+    token_id: &str,
+    account: &str,
+    qty: Balance,
+) -> Result<(), ContractError> {
+    let balance = token.balances(account).peek().await.unwrap_or_default();
+    let new_balance = balance
+        .checked_sub(qty)
+        .ok_or_else(|| ContractError::OwnerBalanceNotEnough(account.to_string()))?;
+
+    snapshot_balance(token_id, account, balance).await;
+
+    if new_balance == Balance::new(0) {
+        token.delete_balances(account).await;
+    } else {
+        token.balances(account).set(&new_balance).await;
+    }
+
+    Ok(())
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Airdrop {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        let from = self.from.unwrap_or_else(|| caller.clone());
+
+        check_can_transfer_from(&caller, &from).await?;
+
+        let token_id = self
+            .token_id
+            .unwrap_or(State::settings().default_token().get().await);
+
+        let token = State::tokens(&token_id)
+            .ok_or(ContractError::TokenNotFound(token_id.clone()))
+            .await?;
+
+        if is_token_paused(&token_id).await {
+            return Err(ContractError::TokenIsPaused(token_id));
+        }
+
+        let skip_invalid = self.skip_invalid;
+        // Returns the error if the airdrop must fail, or records it as the recipient's result
+        let reject = |result: &mut AirdropResult, error: ContractError| {
+            if skip_invalid {
+                result.error = Some(error);
+                Ok(())
+            } else {
+                Err(error)
+            }
+        };
+
+        let mut results = Vec::with_capacity(self.recipients.len());
+        let mut total = Balance::new(0);
+
+        for (recipient, qty) in self.recipients {
+            let mut result = AirdropResult {
+                recipient,
+                qty,
+                error: None,
+            };
+
+            if result.qty.value == 0 {
+                reject(
+                    &mut result,
+                    ContractError::TransferAmountMustBeHigherThanZero,
+                )?;
+            } else if !is_valid_key_segment(&result.recipient) {
+                let error = ContractError::InvalidAddress(result.recipient.clone());
+                reject(&mut result, error)?;
+            } else if result.recipient == from {
+                reject(&mut result, ContractError::TransferFromAndToCannotBeEqual)?;
+            } else {
+                total = total
+                    .checked_add(Balance::new(result.qty.value))
+                    .ok_or(ContractError::BalanceOverflow)?;
+            }
+
+            results.push(result);
+        }
+
+        // The sender's balance is only checked once, against the sum of all valid quantities
+        let from_balance = token.balances(&from).peek().await.unwrap_or_default();
+
+        if from_balance.value < total.value {
+            return Err(ContractError::OwnerBalanceNotEnough(from));
+        }
+
+        let mut credited = Balance::new(0);
+
+        for result in results.iter_mut().filter(|result| result.error.is_none()) {
+            let qty = Balance::new(result.qty.value);
+
+            match add_to_balance(&token, &token_id, &result.recipient, qty).await {
+                // Can't overflow, it's at most `total`
+                Ok(()) => credited = Balance::new(credited.value + qty.value),
+                Err(error) => reject(result, error)?,
+            }
+        }
+
+        remove_from_balance(&token, &token_id, &from, credited).await?;
+
+        // Receivers are only notified once all balances are settled
+        for result in results.iter_mut().filter(|result| result.error.is_none()) {
+            let notification = OnReceived {
+                operator: caller.clone(),
+                from: from.clone(),
+                token_id: token_id.clone(),
+                qty: result.qty,
+            };
+
+            if let Err(error) = notify_receiver(&result.recipient, notification).await {
+                reject(result, error)?;
+
+                let qty = Balance::new(result.qty.value);
+                remove_from_balance(&token, &token_id, &result.recipient, qty).await?;
+                add_to_balance(&token, &token_id, &from, qty).await?;
+            }
+        }
+
+        Ok(HandlerResult::Read(state, ReadResponse::Airdrop(results)))
+    }
+}
@@ -1,11 +1,31 @@
 use async_trait::async_trait;
+use kv_storage::overlay;
 use warp_erc1155::{
-    action::{Action, ActionResult, Batch, HandlerResult, ReadResponse},
+    action::{
+        Action, ActionResult, Batch, BatchActionResult, BatchMode, BatchV2, HandlerResult,
+        ReadResponse, StateChange,
+    },
     error::ContractError,
     state::Parameters,
 };
 
-use crate::{actions::AsyncActionable, contract::execute_action};
+use crate::{
+    actions::AsyncActionable,
+    contract::execute_action,
+    contract_utils::{foreign_call::writes, js_imports},
+};
+
+/// Checks that `action` can be part of a batch, the actions that must be unwrapped or run at the
+/// top level can't.
+fn check_batchable(action: &Action) -> Result<(), ContractError> {
+    match action {
+        Action::Batch(_) | Action::BatchV2(_) => Err(ContractError::ForbiddenNestedBatch),
+        Action::AsDirectCaller(_) | Action::Initialize(_) | Action::Permit(_) => {
+            Err(ContractError::ForbiddenActionInBatch)
+        }
+        _ => Ok(()),
+    }
+}
 
 #[async_trait(?Send)]
 impl AsyncActionable for Batch {
@@ -17,9 +37,7 @@ impl AsyncActionable for Batch {
         let mut none_mode = false;
 
         for action in self.actions {
-            if let Action::Batch(_) = action {
-                return Err(ContractError::ForbiddenNestedBatch);
-            }
+            check_batchable(&action)?;
 
             state = match execute_action(Box::new(action), caller.clone(), state).await? {
                 HandlerResult::Write(state) => {
@@ -60,3 +78,75 @@ impl AsyncActionable for Batch {
         }
     }
 }
+
+/// Executes one of a `BatchV2`'s actions and returns its response, if it's a read
+async fn execute_in_batch(
+    action: Action,
+    caller: String,
+    state: &Parameters,
+) -> Result<Option<ReadResponse>, ContractError> {
+    let (new_state, response) =
+        match execute_action(Box::new(action), caller, state.clone()).await? {
+            HandlerResult::Write(new_state) | HandlerResult::None(new_state) => (new_state, None),
+            HandlerResult::Read(new_state, response) => (new_state, Some(response)),
+        };
+
+    if !StateChange::from_parameters(state, &new_state).is_empty() {
+        return Err(ContractError::CannotChangeParametersInBatch);
+    }
+
+    Ok(response)
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for BatchV2 {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if self.actions.is_empty() {
+            return Err(ContractError::EmptyBatch);
+        }
+
+        for action in &self.actions {
+            check_batchable(action)?;
+        }
+
+        let mut results = Vec::with_capacity(self.actions.len());
+
+        for (index, action) in self.actions.into_iter().enumerate() {
+            let writes_before = writes();
+
+            let result = match self.mode {
+                // Failing the whole interaction rolls back the writes of the previous actions
+                BatchMode::Atomic => execute_in_batch(action, caller.clone(), &state).await,
+                BatchMode::BestEffort => {
+                    overlay::start();
+
+                    let result = execute_in_batch(action, caller.clone(), &state).await;
+
+                    if result.is_ok() {
+                        overlay::commit::<js_imports::Kv>().await;
+                    } else {
+                        overlay::rollback();
+                    }
+
+                    result
+                }
+            };
+
+            results.push(match result {
+                Ok(Some(response)) => BatchActionResult::Read(response),
+                Ok(None) => BatchActionResult::Write,
+                // Notifications of receivers can't be rolled back on their own, the interaction has
+                // to fail for them to be
+                Err(error) if self.mode == BatchMode::Atomic || writes() != writes_before => {
+                    return Err(ContractError::BatchActionFailed {
+                        index: index as u32,
+                        error: Box::new(error),
+                    });
+                }
+                Err(error) => BatchActionResult::Error(error),
+            });
+        }
+
+        Ok(HandlerResult::Read(state, ReadResponse::BatchV2(results)))
+    }
+}
@@ -3,23 +3,39 @@ use std::cmp::Ordering;
 use async_trait::async_trait;
 use warp_erc1155::action::{ActionResult, Burn, HandlerResult};
 use warp_erc1155::error::ContractError;
-use warp_erc1155::state::Parameters;
+use warp_erc1155::state::{Parameters, Role};
 
+use crate::actions::{
+    approval::is_approved_for_all_internal, freeze::is_account_frozen,
+    pause_token::is_token_paused, snapshot::snapshot_balance, AsyncActionable,
+};
 use crate::state::{Balance, State};
-use crate::{actions::AsyncActionable, utils::is_op};
+use crate::utils::{has_role, max_supply};
 
 #[async_trait(?Send)]
 impl AsyncActionable for Burn {
     async fn action(self, caller: String, state: Parameters) -> ActionResult {
-        if !is_op(&caller).await {
-            return Err(ContractError::UnauthorizedAddress(caller));
+        let owner = self.owner.unwrap_or_else(|| caller.clone());
+
+        // Holders and their approved operators can burn freely, burners need to justify themselves
+        if !is_approved_for_all_internal(&caller, &owner).await {
+            if !has_role(Role::Burner, &caller).await {
+                return Err(ContractError::UnauthorizedAddress(caller));
+            }
+
+            let has_reason = self
+                .reason
+                .as_deref()
+                .is_some_and(|reason| !reason.trim().is_empty());
+
+            if !has_reason {
+                return Err(ContractError::BurnReasonRequired);
+            }
         }
 
-        let owner = if let Some(owner) = self.owner {
-            owner.clone()
-        } else {
-            caller
-        };
+        if is_account_frozen(&owner).await {
+            return Err(ContractError::AccountIsFrozen(owner));
+        }
 
         let token_id = self
             .token_id
@@ -29,6 +45,10 @@ impl AsyncActionable for Burn {
             .ok_or(ContractError::TokenNotFound(token_id.clone()))
             .await?;
 
+        if is_token_paused(&token_id).await {
+            return Err(ContractError::TokenIsPaused(token_id));
+        }
+
         let balance = token
             .balances(&owner)
             .peek()
@@ -36,6 +56,8 @@ impl AsyncActionable for Burn {
             .unwrap_or(Balance::new(0))
             .value;
 
+        snapshot_balance(&token_id, &owner, Balance::new(balance)).await;
+
         match balance.cmp(&self.qty.value) {
             Ordering::Less => {
                 return Err(ContractError::OwnerBalanceNotEnough(owner));
@@ -43,7 +65,8 @@ impl AsyncActionable for Burn {
             Ordering::Equal => {
                 token.delete_balances(&owner).await;
 
-                if token.count_balances().await == 0 {
+                // Capped tokens are kept so that their cap can't be lifted by minting them again
+                if token.count_balances().await == 0 && max_supply(&token).await.is_none() {
                     State::delete_tokens(&token_id).await;
                 }
             }
@@ -2,37 +2,70 @@ use async_trait::async_trait;
 
 use warp_erc1155::action::{ActionResult, Configure, HandlerResult};
 use warp_erc1155::error::ContractError;
-use warp_erc1155::state::Parameters;
+use warp_erc1155::state::{Parameters, Role};
 
 use crate::{
-    actions::AsyncActionable,
+    actions::{proxies::init_proxy_permissions, AsyncActionable},
     state::State,
-    utils::{is_op, is_super_op},
+    utils::{apply_legacy_change, has_role, legacy_role_members, role_members},
 };
 
 #[async_trait(?Send)]
 impl AsyncActionable for Configure {
     async fn action(self, caller: String, mut state: Parameters) -> ActionResult {
-        let is_super_op = is_super_op(&caller).await;
-        let is_op = is_op(&caller).await;
-
-        if !is_op
-            || (self.super_operators.is_some() && !is_super_op)
-            || (self.operators.is_some() && !is_super_op)
-            || (self.can_evolve.is_some() && !is_super_op)
-            || (self.proxies.is_some() && !is_super_op)
+        let is_pauser = has_role(Role::Pauser, &caller).await;
+        let is_configurator = has_role(Role::Configurator, &caller).await;
+        let is_evolver = has_role(Role::Evolver, &caller).await;
+        let is_proxy_admin = has_role(Role::ProxyAdmin, &caller).await;
+
+        if !(is_pauser || is_configurator || is_evolver || is_proxy_admin)
+            || (self.paused.is_some() && !is_pauser)
+            || (self.allow_free_transfer.is_some() && !is_pauser)
+            || (self.can_evolve.is_some() && !is_evolver)
+            || (self.proxies.is_some() && !is_proxy_admin)
+            || (self.token_id_policy.is_some() && !is_configurator)
+            || (self.super_operators.is_some() && !is_configurator)
+            || (self.operators.is_some() && !is_configurator)
         {
             return Err(ContractError::UnauthorizedConfiguration);
         }
 
-        if let Some(super_operators) = self.super_operators {
+        // The legacy operator lists are still accepted, the addresses added to or removed from
+        // them being granted or revoked the roles they map to
+        if self.super_operators.is_some() || self.operators.is_some() {
+            let previous_super_operators = State::settings().super_operators().get().await;
+            let previous_operators = State::settings().operators().get().await;
+
+            let super_operators = self
+                .super_operators
+                .unwrap_or_else(|| previous_super_operators.clone());
+            let operators = self.operators.unwrap_or_else(|| previous_operators.clone());
+
+            let mut roles = Vec::new();
+            for role in Role::ALL {
+                let mut members = role_members(role).await;
+                apply_legacy_change(
+                    &mut members,
+                    &legacy_role_members(role, &previous_super_operators, &previous_operators),
+                    &legacy_role_members(role, &super_operators, &operators),
+                );
+
+                // Nobody would be able to grant roles anymore
+                if role == Role::Configurator && members.is_empty() {
+                    return Err(ContractError::CannotRemoveLastConfigurator);
+                }
+
+                roles.push((role, members));
+            }
+
+            for (role, members) in roles {
+                State::roles(&role.to_string()).set(&members).await;
+            }
+
             State::settings()
                 .super_operators()
                 .set(&super_operators)
                 .await;
-        }
-
-        if let Some(operators) = self.operators {
             State::settings().operators().set(&operators).await;
         }
 
@@ -41,6 +74,14 @@ impl AsyncActionable for Configure {
         }
 
         if let Some(proxies) = self.proxies {
+            let previous_proxies = State::settings().proxies().get().await;
+            let added_proxies = proxies
+                .iter()
+                .filter(|proxy| !previous_proxies.contains(proxy))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            init_proxy_permissions(&added_proxies).await;
             State::settings().proxies().set(&proxies).await;
         }
 
@@ -55,6 +96,13 @@ impl AsyncActionable for Configure {
                 .await;
         }
 
+        if let Some(token_id_policy) = self.token_id_policy {
+            State::settings()
+                .token_id_policy()
+                .set(&token_id_policy)
+                .await;
+        }
+
         if let Some(_) = self.can_evolve {
             Ok(HandlerResult::Write(state))
         } else {
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+
+use warp_erc1155::action::{
+    ActionResult, Freeze, GetFrozenAccounts, HandlerResult, ReadResponse, Unfreeze,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::{Parameters, Role};
+
+use crate::{actions::AsyncActionable, state::State, utils::has_role};
+
+pub async fn is_account_frozen(account: &str) -> bool {
+    State::frozen_accounts(account).exists().await
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Freeze {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Pauser, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        State::frozen_accounts(&self.account).set(&true).await;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Unfreeze {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Pauser, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        if is_account_frozen(&self.account).await {
+            State::delete_frozen_accounts(&self.account).await;
+        }
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetFrozenAccounts {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let accounts = State::list_frozen_accounts()
+            .await
+            .into_iter()
+            .map(|(account, _)| account)
+            .collect();
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetFrozenAccounts(accounts),
+        ))
+    }
+}
@@ -9,6 +9,7 @@ use warp_erc1155::{
 use crate::actions::AsyncActionable;
 
 use crate::state::State;
+use crate::utils::max_supply;
 
 #[async_trait(?Send)]
 impl AsyncActionable for GetAllTokens {
@@ -24,6 +25,9 @@ impl AsyncActionable for GetAllTokens {
                     balances: HashMap::from_iter(kv_token.list_balances().await.iter().map(
                         |(address, balance)| (address.clone(), StateBalance::new(balance.value)),
                     )),
+                    max_supply: max_supply(&kv_token)
+                        .await
+                        .map(|max| StateBalance::new(max.value)),
                 };
             tokens.push((token_id, token));
         }
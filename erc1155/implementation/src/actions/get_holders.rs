@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use warp_erc1155::{
+    action::{ActionResult, GetHolders, HandlerResult, ReadResponse},
+    error::ContractError,
+    state::{Balance as StateBalance, Parameters},
+};
+
+use crate::actions::AsyncActionable;
+
+use crate::state::State;
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetHolders {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let token_id = self
+            .token_id
+            .unwrap_or(State::settings().default_token().get().await);
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(ContractError::InvalidLimit(limit));
+        }
+
+        let token = State::tokens(&token_id)
+            .ok_or(ContractError::TokenNotFound(token_id.clone()))
+            .await?;
+
+        // KV lists are sorted by key
+        let balances = token.list_balances().await;
+        let holders_count = balances.len() as u32;
+
+        let mut remaining = balances
+            .into_iter()
+            .filter(|(address, _)| self.after.as_ref().map_or(true, |after| address > after))
+            .peekable();
+
+        let holders = remaining
+            .by_ref()
+            .take(limit as usize)
+            .map(|(address, balance)| (address, StateBalance::new(balance.value)))
+            .collect::<Vec<_>>();
+
+        let next = if remaining.peek().is_some() {
+            holders.last().map(|(address, _)| address.clone())
+        } else {
+            None
+        };
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetHolders {
+                token_id,
+                holders,
+                holders_count,
+                next,
+            },
+        ))
+    }
+}
@@ -10,6 +10,7 @@ use warp_erc1155::{
 use crate::actions::AsyncActionable;
 
 use crate::state::State;
+use crate::utils::max_supply;
 
 #[async_trait(?Send)]
 impl AsyncActionable for GetToken {
@@ -32,6 +33,9 @@ impl AsyncActionable for GetToken {
                     .iter()
                     .map(|(address, balance)| (address.clone(), StateBalance::new(balance.value))),
             ),
+            max_supply: max_supply(&kv_token)
+                .await
+                .map(|max| StateBalance::new(max.value)),
         };
 
         Ok(HandlerResult::Read(
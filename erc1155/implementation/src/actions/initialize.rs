@@ -4,12 +4,13 @@ use async_trait::async_trait;
 use warp_erc1155::{
     action::{ActionResult, HandlerResult, Initialize},
     error::ContractError,
-    state::Parameters,
+    state::{Parameters, Role},
 };
 
 use crate::{
     actions::AsyncActionable,
     state::{Approvals, Settings, Token},
+    utils::{legacy_role_members, validate_initial_state},
 };
 
 use crate::state::{Balance, State};
@@ -18,6 +19,12 @@ use crate::state::{Balance, State};
 impl AsyncActionable for Initialize {
     async fn action(self, _caller: String, mut parameters: Parameters) -> ActionResult {
         if let Some(init_state) = parameters.initial_state {
+            let violations = validate_initial_state(&init_state);
+
+            if !violations.is_empty() {
+                return Err(ContractError::InvalidInitialState(violations));
+            }
+
             let state = &State {
                 ticker_nonce: init_state.ticker_nonce,
                 tokens: HashMap::from_iter(init_state.tokens.iter().map(|(id, token)| {
@@ -29,6 +36,7 @@ impl AsyncActionable for Initialize {
                             balances: HashMap::from_iter(token.balances.iter().map(
                                 |(address, balance)| (address.clone(), Balance::new(balance.value)),
                             )),
+                            max_supply: token.max_supply.map(|max| Balance::new(max.value)),
                         },
                     )
                 })),
@@ -54,7 +62,41 @@ impl AsyncActionable for Initialize {
                     operators: init_state.settings.operators.clone(),
                     proxies: init_state.settings.proxies.clone(),
                     allow_free_transfer: init_state.settings.allow_free_transfer,
+                    token_id_policy: init_state.settings.token_id_policy.clone(),
+                },
+                roles: HashMap::from_iter(Role::ALL.iter().map(|role| {
+                    let members =
+                        init_state
+                            .settings
+                            .roles
+                            .get(role)
+                            .cloned()
+                            .unwrap_or_else(|| {
+                                legacy_role_members(
+                                    *role,
+                                    &init_state.settings.super_operators,
+                                    &init_state.settings.operators,
+                                )
+                            });
+
+                    (role.to_string(), members)
+                })),
+                frozen_accounts: HashMap::new(),
+                paused_tokens: HashMap::new(),
+                proxy_permissions: {
+                    // Stored for every proxy, the ones missing being left without permission
+                    let mut permissions = init_state.settings.proxy_permissions.clone();
+                    for proxy in &init_state.settings.proxies {
+                        permissions.entry(proxy.clone()).or_default();
+                    }
+
+                    permissions
                 },
+                receivers: HashMap::new(),
+                snapshot_id: 0,
+                snapshots: HashMap::new(),
+                balance_snapshots: HashMap::new(),
+                permit_nonces: HashMap::new(),
             };
 
             State::init(state).await;
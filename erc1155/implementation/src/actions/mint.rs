@@ -1,16 +1,19 @@
 use async_trait::async_trait;
 
 use warp_erc1155::{
-    action::{ActionResult, HandlerResult, Mint},
+    action::{ActionResult, HandlerResult, Mint, OnReceived},
     error::ContractError,
-    state::Parameters,
+    state::{Parameters, Role},
 };
 
 use crate::{
-    actions::AsyncActionable,
+    actions::{receivers::notify_receiver, snapshot::snapshot_balance, AsyncActionable},
     contract_utils::js_imports::Transaction,
-    state::{State, Token},
-    utils::is_op,
+    state::{Balance, State, Token},
+    utils::{
+        has_role, is_token_id_reserved, max_supply, token_id_policy, total_supply,
+        validate_token_id,
+    },
 };
 
 fn get_token_id(prefix: Option<String>, base_id: Option<String>) -> String {
@@ -25,35 +28,88 @@ impl AsyncActionable for Mint {
             return Err(ContractError::TransferAmountMustBeHigherThanZero);
         }
 
-        if !is_op(&caller).await {
+        if !has_role(Role::Minter, &caller).await {
             return Err(ContractError::UnauthorizedAddress(caller));
         }
 
         let token_id = get_token_id(self.prefix, self.base_id);
 
-        token_id.chars().all(|c| c.is_alphanumeric() || c == '-');
+        let policy = token_id_policy().await;
+
+        validate_token_id(&policy, &token_id)?;
+
+        if is_token_id_reserved(&policy, &token_id) && !has_role(Role::Configurator, &caller).await
+        {
+            return Err(ContractError::InvalidTokenId(token_id));
+        }
+
+        let (max_supply, supply) = if State::tokens(&token_id).exists().await {
+            let token = State::tokens(&token_id)
+                .ok_or(ContractError::TokenNotFound(token_id.clone()))
+                .await?;
+            let max_supply = max_supply(&token).await;
+
+            if self
+                .max_supply
+                .is_some_and(|max| Some(max.value) != max_supply.map(|max| max.value))
+            {
+                return Err(ContractError::MaxSupplyIsImmutable(token_id));
+            }
+
+            // The total supply takes reading every balance, it only matters to capped tokens
+            let supply = match max_supply {
+                Some(_) => total_supply(&token).await?,
+                None => Balance::new(0),
+            };
+
+            (max_supply.map(|max| max.value), supply)
+        } else {
+            (self.max_supply.map(|max| max.value), Balance::new(0))
+        };
+
+        let new_supply = supply
+            .checked_add(Balance::new(self.qty.value))
+            .ok_or(ContractError::BalanceOverflow)?;
+
+        if max_supply.is_some_and(|max| new_supply.value > max) {
+            return Err(ContractError::MaxSupplyExceeded(token_id));
+        }
 
         let default_token = State::settings().default_token().get().await;
         let ticker_nonce = State::ticker_nonce().get().await;
 
-        State::tokens(&token_id)
+        let token = State::tokens(&token_id)
             .init(Token {
                 ticker: format!("{}{}", default_token, ticker_nonce),
                 tx_id: Some(Transaction::id()),
+                max_supply: max_supply.map(Balance::new),
                 ..Default::default()
             })
-            .await
-            .balances(&caller)
-            .init_default()
-            .await
-            .map(|mut balances| {
-                balances.value += self.qty.value;
-                balances
-            })
             .await;
 
+        let target = self.target.unwrap_or_else(|| caller.clone());
+
+        let balance = token.balances(&target).peek().await.unwrap_or_default();
+        let new_balance = balance
+            .checked_add(Balance::new(self.qty.value))
+            .ok_or(ContractError::BalanceOverflow)?;
+
+        snapshot_balance(&token_id, &target, balance).await;
+        token.balances(&target).set(&new_balance).await;
+
         State::ticker_nonce().map(|nonce| nonce + 1).await;
 
+        notify_receiver(
+            &target,
+            OnReceived {
+                operator: caller,
+                from: String::new(),
+                token_id,
+                qty: self.qty,
+            },
+        )
+        .await?;
+
         Ok(HandlerResult::Write(state))
     }
 }
@@ -1,17 +1,27 @@
 use async_trait::async_trait;
 use warp_erc1155::{action::ActionResult, state::Parameters};
 
+pub mod airdrop;
 pub mod approval;
 pub mod balance;
 pub mod batch;
 pub mod burn;
 pub mod configure;
 pub mod evolve;
+pub mod freeze;
 pub mod get_all_tokens;
+pub mod get_holders;
 pub mod get_token;
 pub mod initialize;
 pub mod mint;
+pub mod pause_token;
+pub mod permit;
+pub mod proxies;
 pub mod read_settings;
+pub mod receivers;
+pub mod roles;
+pub mod simulate;
+pub mod snapshot;
 pub mod transfer;
 
 pub trait Actionable {
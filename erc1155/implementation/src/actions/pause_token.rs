@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+
+use warp_erc1155::action::{
+    ActionResult, GetPausedTokens, HandlerResult, PauseToken, ReadResponse, UnpauseToken,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::{Parameters, Role};
+
+use crate::{actions::AsyncActionable, state::State, utils::has_role};
+
+pub async fn is_token_paused(token_id: &str) -> bool {
+    State::paused_tokens(token_id).exists().await
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for PauseToken {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Pauser, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        if !State::tokens(&self.token_id).exists().await {
+            return Err(ContractError::TokenNotFound(self.token_id));
+        }
+
+        State::paused_tokens(&self.token_id).set(&true).await;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for UnpauseToken {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Pauser, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        if is_token_paused(&self.token_id).await {
+            State::delete_paused_tokens(&self.token_id).await;
+        }
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetPausedTokens {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let token_ids = State::list_paused_tokens()
+            .await
+            .into_iter()
+            .map(|(token_id, _)| token_id)
+            .collect();
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetPausedTokens(token_ids),
+        ))
+    }
+}
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rsa::{BigUint, Pss, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+use warp_erc1155::action::{ActionResult, GetPermitNonce, HandlerResult, Permit, ReadResponse};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::Parameters;
+
+use crate::{
+    actions::AsyncActionable,
+    contract_utils::js_imports::{Block, Contract},
+    state::State,
+};
+
+/// Arweave keys always use 65537 as their public exponent
+const PUBLIC_EXPONENT: u32 = 65537;
+const SHA256_LEN: usize = 32;
+/// Arweave keys are 4096 bits long
+const MIN_MODULUS_LEN: usize = 512;
+
+/// Checks that `signature` was made by the RSA key of modulus `public_key` and that this key is
+/// the one of the Arweave address `owner`.
+fn verify_arweave_signature(owner: &str, public_key: &str, message: &str, signature: &str) -> bool {
+    let (Ok(modulus), Ok(signature)) = (
+        URL_SAFE_NO_PAD.decode(public_key),
+        URL_SAFE_NO_PAD.decode(signature),
+    ) else {
+        return false;
+    };
+
+    if modulus.len() < MIN_MODULUS_LEN || URL_SAFE_NO_PAD.encode(Sha256::digest(&modulus)) != owner
+    {
+        return false;
+    }
+
+    let Ok(key) = RsaPublicKey::new(
+        BigUint::from_bytes_be(&modulus),
+        BigUint::from(PUBLIC_EXPONENT),
+    ) else {
+        return false;
+    };
+
+    let hashed = Sha256::digest(message.as_bytes());
+
+    // Browser wallets sign with 32 bytes salts while arweave-js on node uses the longest salt
+    // allowed by the key
+    let Some(max_salt_len) = modulus.len().checked_sub(SHA256_LEN + 2) else {
+        return false;
+    };
+
+    [SHA256_LEN, max_salt_len].iter().any(|salt_len| {
+        key.verify(Pss::new_with_salt::<Sha256>(*salt_len), &hashed, &signature)
+            .is_ok()
+    })
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetPermitNonce {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let nonce = State::permit_nonces(&self.owner).peek().await.unwrap_or(0);
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetPermitNonce {
+                owner: self.owner,
+                nonce,
+            },
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Permit {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        if Block::height() as i64 > self.deadline as i64 {
+            return Err(ContractError::PermitExpired);
+        }
+
+        let nonce = State::permit_nonces(&self.owner).peek().await.unwrap_or(0);
+
+        if self.nonce != nonce {
+            return Err(ContractError::InvalidPermitNonce(nonce));
+        }
+
+        if !verify_arweave_signature(
+            &self.owner,
+            &self.public_key,
+            &self.message(&Contract::id()),
+            &self.signature,
+        ) {
+            return Err(ContractError::InvalidPermitSignature);
+        }
+
+        State::permit_nonces(&self.owner).set(&(nonce + 1)).await;
+
+        State::approvals(&self.owner)
+            .init_default()
+            .await
+            .approves(&self.operator)
+            .set(&self.approved)
+            .await;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner_of(modulus: &[u8]) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(modulus))
+    }
+
+    #[test]
+    fn rejects_keys_shorter_than_arweave_ones() {
+        for len in [0, 1, SHA256_LEN + 1, MIN_MODULUS_LEN - 1] {
+            let modulus = vec![0xff; len];
+
+            assert!(!verify_arweave_signature(
+                &owner_of(&modulus),
+                &URL_SAFE_NO_PAD.encode(&modulus),
+                "message",
+                &URL_SAFE_NO_PAD.encode([0xff; 8]),
+            ));
+        }
+    }
+}
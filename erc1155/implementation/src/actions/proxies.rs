@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use warp_erc1155::action::{
+    ActionResult, GetProxies, HandlerResult, ReadResponse, SetProxyPermissions,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::{Parameters, ProxyPermission, Role};
+
+use crate::{actions::AsyncActionable, state::State, utils::has_role};
+
+/// Proxies configured before permissions existed have none stored, they keep every permission.
+/// The ones configured since can't perform any action until they are given permissions, see
+/// `init_proxy_permissions`.
+pub async fn proxy_permissions(proxy: &str) -> Vec<ProxyPermission> {
+    State::proxy_permissions(proxy)
+        .peek()
+        .await
+        .unwrap_or_else(|| ProxyPermission::ALL.to_vec())
+}
+
+/// Gives no permission to the `proxies` that don't have any stored yet
+pub async fn init_proxy_permissions(proxies: &[String]) {
+    for proxy in proxies {
+        if !State::proxy_permissions(proxy).exists().await {
+            State::proxy_permissions(proxy).set(&Vec::new()).await;
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for SetProxyPermissions {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::ProxyAdmin, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        State::proxy_permissions(&self.proxy)
+            .set(&self.permissions)
+            .await;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetProxies {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let mut proxies = HashMap::new();
+
+        for proxy in State::settings().proxies().get().await {
+            let permissions = proxy_permissions(&proxy).await;
+            proxies.insert(proxy, permissions);
+        }
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetProxies(proxies),
+        ))
+    }
+}
@@ -1,25 +1,42 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use warp_erc1155::{
     action::{ActionResult, HandlerResult, ReadResponse, ReadSettings},
-    state::{Parameters, Settings},
+    state::{Parameters, Role, Settings},
 };
 
-use crate::actions::AsyncActionable;
+use crate::actions::{proxies::proxy_permissions, AsyncActionable};
 
 use crate::state::State;
+use crate::utils::{role_members, token_id_policy};
 
 #[async_trait(?Send)]
 impl AsyncActionable for ReadSettings {
     async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let mut roles = HashMap::new();
+        for role in Role::ALL {
+            roles.insert(role, role_members(role).await);
+        }
+
+        let proxies = State::settings().proxies().get().await;
+        let mut permissions = HashMap::new();
+        for proxy in &proxies {
+            permissions.insert(proxy.clone(), proxy_permissions(proxy).await);
+        }
+
         Ok(HandlerResult::Read(
             state,
             ReadResponse::ReadSettings(Settings {
                 default_token: State::settings().default_token().get().await,
                 paused: State::settings().paused().get().await,
+                roles,
                 super_operators: State::settings().super_operators().get().await,
                 operators: State::settings().operators().get().await,
-                proxies: State::settings().proxies().get().await,
+                proxies,
+                proxy_permissions: permissions,
                 allow_free_transfer: State::settings().allow_free_transfer().get().await,
+                token_id_policy: token_id_policy().await,
             }),
         ))
     }
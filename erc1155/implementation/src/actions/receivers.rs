@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+
+use warp_erc1155::action::{
+    ActionResult, HandlerResult, IsReceiver, OnReceived, ReadResponse, ReceiverAction,
+    RegisterReceiver, UnregisterReceiver,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::Parameters;
+
+use crate::{
+    actions::AsyncActionable,
+    contract_utils::{
+        foreign_call::try_write_foreign_contract,
+        js_imports::{SmartWeave, Transaction},
+    },
+    state::State,
+};
+
+pub async fn is_receiver(address: &str) -> bool {
+    State::receivers(address).exists().await
+}
+
+/// Notifies `target` of an incoming transfer if it registered as a receiver.
+///
+/// Receivers aren't notified of the transfers they initiate themselves, they would otherwise be
+/// called back while still executing.
+pub async fn notify_receiver(target: &str, notification: OnReceived) -> Result<(), ContractError> {
+    if !is_receiver(target).await || SmartWeave::caller() == target {
+        return Ok(());
+    }
+
+    if try_write_foreign_contract(target, ReceiverAction::OnReceived(notification)).await {
+        Ok(())
+    } else {
+        Err(ContractError::ReceiverRejected(target.to_string()))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for RegisterReceiver {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        // Only contracts can handle notifications, wallets would reject every incoming transfer
+        if caller == Transaction::owner() {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        State::receivers(&caller).set(&true).await;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for UnregisterReceiver {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if is_receiver(&caller).await {
+            State::delete_receivers(&caller).await;
+        }
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for IsReceiver {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let registered = is_receiver(&self.address).await;
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::IsReceiver {
+                address: self.address,
+                registered,
+            },
+        ))
+    }
+}
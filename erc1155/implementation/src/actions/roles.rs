@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+
+use warp_erc1155::action::{
+    ActionResult, GrantRole, HandlerResult, HasRole, ReadResponse, RenounceRole, RevokeRole,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::{Parameters, Role};
+
+use crate::{
+    actions::AsyncActionable,
+    state::State,
+    utils::{has_role, role_members},
+};
+
+async fn remove_role_member(role: Role, account: &str) -> Result<(), ContractError> {
+    let mut members = role_members(role).await;
+    members.retain(|member| member != account);
+
+    // Nobody would be able to grant roles anymore
+    if role == Role::Configurator && members.is_empty() {
+        return Err(ContractError::CannotRemoveLastConfigurator);
+    }
+
+    State::roles(&role.to_string()).set(&members).await;
+
+    Ok(())
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for HasRole {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let has_role = has_role(self.role, &self.account).await;
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::HasRole {
+                has_role,
+                role: self.role,
+                account: self.account,
+            },
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GrantRole {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Configurator, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        let mut members = role_members(self.role).await;
+
+        if !members.contains(&self.account) {
+            members.push(self.account);
+            State::roles(&self.role.to_string()).set(&members).await;
+        }
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for RevokeRole {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Configurator, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        remove_role_member(self.role, &self.account).await?;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for RenounceRole {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        remove_role_member(self.role, &caller).await?;
+
+        Ok(HandlerResult::None(state))
+    }
+}
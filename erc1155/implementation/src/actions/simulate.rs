@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+
+use warp_common::simulation::simulate;
+use warp_erc1155::action::{ActionResult, HandlerResult, ReadResponse, Simulate};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::Parameters;
+
+use crate::{
+    actions::AsyncActionable,
+    contract::handle,
+    contract_utils::{
+        foreign_call::{finish_simulation, start_simulation},
+        js_imports,
+    },
+};
+
+#[async_trait(?Send)]
+impl AsyncActionable for Simulate {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        if !start_simulation() {
+            return Err(ContractError::ForbiddenNestedSimulation);
+        }
+
+        let mut simulation = simulate::<js_imports::Kv, _, _, _>(&state, async {
+            Ok(match handle(state.clone(), *self.action).await? {
+                HandlerResult::Write(new_state) | HandlerResult::None(new_state) => {
+                    (new_state, None)
+                }
+                HandlerResult::Read(new_state, response) => (new_state, Some(response)),
+            })
+        })
+        .await;
+
+        let foreign_writes = finish_simulation();
+        if simulation.error.is_none() {
+            simulation.foreign_writes = foreign_writes;
+        }
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::Simulate(Box::new(simulation)),
+        ))
+    }
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+
+use warp_erc1155::action::{
+    ActionResult, BalanceOfAt, GetSnapshots, HandlerResult, ReadResponse, TakeSnapshot,
+};
+use warp_erc1155::error::ContractError;
+use warp_erc1155::state::{Balance as StateBalance, Parameters, Role, Snapshot};
+
+use crate::{
+    actions::AsyncActionable,
+    contract_utils::js_imports::Block,
+    state::{Balance, SnapshotBalance, State},
+    utils::has_role,
+};
+
+/// Contracts initialized before snapshots existed don't have the id stored
+async fn current_snapshot_id() -> u32 {
+    State::snapshot_id().peek().await.unwrap_or(0)
+}
+
+/// Must be called before any change to the balance of `account`, with its current value, so that
+/// it can be read at the snapshots taken since its last change.
+pub async fn snapshot_balance(token_id: &str, account: &str, balance: Balance) {
+    let snapshot_id = current_snapshot_id().await;
+
+    if snapshot_id == 0 {
+        return;
+    }
+
+    let mut history = State::balance_snapshots(token_id)
+        .peek()
+        .accounts(account)
+        .await
+        .unwrap_or_default();
+
+    // Only the balance before the first change following a snapshot matters
+    let is_recorded = history
+        .last()
+        .is_some_and(|last| last.snapshot_id == snapshot_id);
+
+    if !is_recorded {
+        history.push(SnapshotBalance {
+            snapshot_id,
+            balance,
+        });
+
+        State::balance_snapshots(token_id)
+            .init_default()
+            .await
+            .accounts(account)
+            .set(&history)
+            .await;
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for TakeSnapshot {
+    async fn action(self, caller: String, state: Parameters) -> ActionResult {
+        if !has_role(Role::Configurator, &caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        let snapshot_id = current_snapshot_id().await + 1;
+
+        State::snapshots(&snapshot_id.to_string())
+            .set(&(Block::height() as u32))
+            .await;
+        State::snapshot_id().set(&snapshot_id).await;
+
+        Ok(HandlerResult::None(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetSnapshots {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        let mut snapshots = Vec::new();
+        for id in 1..=current_snapshot_id().await {
+            snapshots.push(Snapshot {
+                id,
+                block_height: State::snapshots(&id.to_string()).peek().await.unwrap_or(0),
+            });
+        }
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetSnapshots(snapshots),
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for BalanceOfAt {
+    async fn action(self, _caller: String, state: Parameters) -> ActionResult {
+        if self.snapshot_id == 0 || self.snapshot_id > current_snapshot_id().await {
+            return Err(ContractError::SnapshotNotFound(self.snapshot_id));
+        }
+
+        let token_id = self
+            .token_id
+            .unwrap_or(State::settings().default_token().get().await);
+
+        let history = State::balance_snapshots(&token_id)
+            .peek()
+            .accounts(&self.target)
+            .await
+            .unwrap_or_default();
+
+        // The first change made at or after the snapshot recorded the balance it had then,
+        // without any change since the balance is still the same
+        let balance = match history
+            .iter()
+            .find(|entry| entry.snapshot_id >= self.snapshot_id)
+        {
+            Some(entry) => entry.balance,
+            None => State::tokens(&token_id)
+                .peek()
+                .balances(&self.target)
+                .await
+                .unwrap_or_default(),
+        };
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::BalanceOfAt {
+                balance: StateBalance::new(balance.value),
+                target: self.target,
+                snapshot_id: self.snapshot_id,
+            },
+        ))
+    }
+}
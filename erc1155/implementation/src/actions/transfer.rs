@@ -1,15 +1,35 @@
 use async_trait::async_trait;
 
-use warp_erc1155::action::{ActionResult, HandlerResult, Transfer};
+use warp_erc1155::action::{ActionResult, HandlerResult, OnReceived, Transfer};
 use warp_erc1155::error::ContractError;
-use warp_erc1155::state::Parameters as StateLegacy;
+use warp_erc1155::state::{Parameters as StateLegacy, Role};
 
 use crate::{
-    actions::{approval::is_approved_for_all_internal, AsyncActionable},
+    actions::{
+        approval::is_approved_for_all_internal, freeze::is_account_frozen,
+        pause_token::is_token_paused, receivers::notify_receiver, snapshot::snapshot_balance,
+        AsyncActionable,
+    },
     state::{Balance, State},
-    utils::is_op,
+    utils::has_role,
 };
 
+/// Checks that `caller` can move tokens out of `from`'s balance
+pub async fn check_can_transfer_from(caller: &str, from: &str) -> Result<(), ContractError> {
+    if !is_approved_for_all_internal(caller, from).await
+        || (!State::settings().allow_free_transfer().get().await
+            && !has_role(Role::Minter, caller).await)
+    {
+        return Err(ContractError::UnauthorizedAddress(caller.to_string()));
+    }
+
+    if is_account_frozen(from).await {
+        return Err(ContractError::AccountIsFrozen(from.to_string()));
+    }
+
+    Ok(())
+}
+
 #[async_trait(?Send)]
 impl AsyncActionable for Transfer {
     async fn action(self, caller: String, state: StateLegacy) -> ActionResult {
@@ -23,11 +43,7 @@ impl AsyncActionable for Transfer {
             caller.clone()
         };
 
-        if !is_approved_for_all_internal(&caller, &from).await
-            || (!State::settings().allow_free_transfer().get().await && !is_op(&caller).await)
-        {
-            return Err(ContractError::UnauthorizedAddress(caller));
-        }
+        check_can_transfer_from(&caller, &from).await?;
 
         if from == self.target {
             return Err(ContractError::TransferFromAndToCannotBeEqual);
@@ -41,17 +57,31 @@ impl AsyncActionable for Transfer {
             .ok_or(ContractError::TokenNotFound(token_id.clone()))
             .await?;
 
+        if is_token_paused(&token_id).await {
+            return Err(ContractError::TokenIsPaused(token_id));
+        }
+
         let from_balance = token
             .balances(&from)
             .peek()
             .await
             .unwrap_or(Balance::new(0));
 
-        if from_balance.value < self.qty.value {
-            return Err(ContractError::OwnerBalanceNotEnough(from));
-        }
+        let from_new_balance = from_balance
+            .checked_sub(Balance::new(self.qty.value))
+            .ok_or_else(|| ContractError::OwnerBalanceNotEnough(from.clone()))?;
+
+        let target_balance = token
+            .balances(&self.target)
+            .peek()
+            .await
+            .unwrap_or_default();
+        let target_new_balance = target_balance
+            .checked_add(Balance::new(self.qty.value))
+            .ok_or(ContractError::BalanceOverflow)?;
 
-        let from_new_balance = Balance::new(from_balance.value - self.qty.value);
+        snapshot_balance(&token_id, &from, from_balance).await;
+        snapshot_balance(&token_id, &self.target, target_balance).await;
 
         if from_new_balance == Balance::new(0) {
             token.delete_balances(&from).await;
@@ -59,12 +89,18 @@ impl AsyncActionable for Transfer {
             token.balances(&from).set(&from_new_balance).await;
         }
 
-        token
-            .balances(&self.target)
-            .init(Balance::new(0))
-            .await
-            .map(|target_balance| Balance::new(target_balance.value + self.qty.value))
-            .await;
+        token.balances(&self.target).set(&target_new_balance).await;
+
+        notify_receiver(
+            &self.target,
+            OnReceived {
+                operator: caller,
+                from,
+                token_id,
+                qty: self.qty,
+            },
+        )
+        .await?;
 
         Ok(HandlerResult::None(state))
     }
@@ -2,10 +2,10 @@ use async_recursion::async_recursion;
 
 use warp_erc1155::action::{Action, ActionResult};
 use warp_erc1155::error::ContractError;
-use warp_erc1155::state::Parameters;
+use warp_erc1155::state::{Parameters, ProxyPermission};
 
 use crate::{
-    actions::AsyncActionable,
+    actions::{proxies::proxy_permissions, AsyncActionable},
     contract_utils::js_imports::{SmartWeave, Transaction},
     state::State,
 };
@@ -14,13 +14,85 @@ pub fn allowed_in_pause(action: &Action) -> bool {
     matches!(
         action,
         Action::Configure(_)
+            | Action::Simulate(_)
             | Action::GetToken(_)
             | Action::GetAllTokens(_)
+            | Action::GetHolders(_)
+            | Action::IsReceiver(_)
+            | Action::GetPermitNonce(_)
             | Action::BalanceOf(_)
+            | Action::BalanceOfAt(_)
+            | Action::Snapshot(_)
+            | Action::GetSnapshots(_)
             | Action::ReadSettings(_)
+            | Action::GrantRole(_)
+            | Action::RevokeRole(_)
+            | Action::RenounceRole(_)
+            | Action::HasRole(_)
+            | Action::SetProxyPermissions(_)
+            | Action::GetProxies(_)
+            | Action::Freeze(_)
+            | Action::Unfreeze(_)
+            | Action::GetFrozenAccounts(_)
+            | Action::PauseToken(_)
+            | Action::UnpauseToken(_)
+            | Action::GetPausedTokens(_)
     )
 }
 
+/// Permissions that a proxy needs to perform `action` on behalf of the transaction's signer
+pub fn required_proxy_permissions(action: &Action) -> Vec<ProxyPermission> {
+    match action {
+        Action::Transfer(_) | Action::Airdrop(_) => vec![ProxyPermission::Transfer],
+        Action::Mint(_) => vec![ProxyPermission::Mint],
+        Action::Burn(_) => vec![ProxyPermission::Burn],
+        Action::SetApprovalForAll(_) => vec![ProxyPermission::Approve],
+        Action::Configure(_)
+        | Action::Snapshot(_)
+        | Action::RegisterReceiver(_)
+        | Action::UnregisterReceiver(_)
+        | Action::GrantRole(_)
+        | Action::RevokeRole(_)
+        | Action::RenounceRole(_)
+        | Action::SetProxyPermissions(_)
+        | Action::Freeze(_)
+        | Action::Unfreeze(_)
+        | Action::PauseToken(_)
+        | Action::UnpauseToken(_)
+        | Action::Evolve(_) => vec![ProxyPermission::Administer],
+        Action::Batch(batch) => batch
+            .actions
+            .iter()
+            .flat_map(required_proxy_permissions)
+            .collect(),
+        Action::BatchV2(batch) => batch
+            .actions
+            .iter()
+            .flat_map(required_proxy_permissions)
+            .collect(),
+        // Permits are authorized by their signature, not by the caller
+        Action::Permit(_)
+        | Action::Initialize(_)
+        | Action::AsDirectCaller(_)
+        // The wrapped action is checked on its own when it's simulated
+        | Action::Simulate(_)
+        | Action::BalanceOf(_)
+        | Action::BalanceOfAt(_)
+        | Action::GetSnapshots(_)
+        | Action::GetToken(_)
+        | Action::GetAllTokens(_)
+        | Action::GetHolders(_)
+        | Action::ReadSettings(_)
+        | Action::HasRole(_)
+        | Action::GetProxies(_)
+        | Action::IsReceiver(_)
+        | Action::GetFrozenAccounts(_)
+        | Action::GetPausedTokens(_)
+        | Action::IsApprovedForAll(_)
+        | Action::GetPermitNonce(_) => vec![],
+    }
+}
+
 pub async fn execute_action(
     action: Box<Action>,
     effective_caller: String,
@@ -29,18 +101,42 @@ pub async fn execute_action(
     match *action {
         Action::Initialize(_) => Err(ContractError::ContractAlreadyInitialized),
         Action::AsDirectCaller(_) => unreachable!("AsDirectCaller wasn't properly unwrapped"),
+        Action::Simulate(action) => action.action(effective_caller, state).await,
         Action::GetToken(action) => action.action(effective_caller, state).await,
         Action::GetAllTokens(action) => action.action(effective_caller, state).await,
+        Action::GetHolders(action) => action.action(effective_caller, state).await,
         Action::BalanceOf(action) => action.action(effective_caller, state).await,
+        Action::BalanceOfAt(action) => action.action(effective_caller, state).await,
+        Action::Snapshot(action) => action.action(effective_caller, state).await,
+        Action::GetSnapshots(action) => action.action(effective_caller, state).await,
         Action::ReadSettings(action) => action.action(effective_caller, state).await,
         Action::Transfer(action) => action.action(effective_caller, state).await,
+        Action::Airdrop(action) => action.action(effective_caller, state).await,
         Action::Configure(action) => action.action(effective_caller, state).await,
+        Action::GrantRole(action) => action.action(effective_caller, state).await,
+        Action::RevokeRole(action) => action.action(effective_caller, state).await,
+        Action::RenounceRole(action) => action.action(effective_caller, state).await,
+        Action::HasRole(action) => action.action(effective_caller, state).await,
+        Action::SetProxyPermissions(action) => action.action(effective_caller, state).await,
+        Action::GetProxies(action) => action.action(effective_caller, state).await,
+        Action::RegisterReceiver(action) => action.action(effective_caller, state).await,
+        Action::UnregisterReceiver(action) => action.action(effective_caller, state).await,
+        Action::IsReceiver(action) => action.action(effective_caller, state).await,
+        Action::Freeze(action) => action.action(effective_caller, state).await,
+        Action::Unfreeze(action) => action.action(effective_caller, state).await,
+        Action::GetFrozenAccounts(action) => action.action(effective_caller, state).await,
+        Action::PauseToken(action) => action.action(effective_caller, state).await,
+        Action::UnpauseToken(action) => action.action(effective_caller, state).await,
+        Action::GetPausedTokens(action) => action.action(effective_caller, state).await,
         Action::Evolve(action) => action.action(effective_caller, state).await,
         Action::SetApprovalForAll(action) => action.action(effective_caller, state).await,
         Action::IsApprovedForAll(action) => action.action(effective_caller, state).await,
+        Action::Permit(action) => action.action(effective_caller, state).await,
+        Action::GetPermitNonce(action) => action.action(effective_caller, state).await,
         Action::Mint(action) => action.action(effective_caller, state).await,
         Action::Burn(action) => action.action(effective_caller, state).await,
         Action::Batch(action) => action.action(effective_caller, state).await,
+        Action::BatchV2(action) => action.action(effective_caller, state).await,
     }
 }
 
@@ -70,6 +166,15 @@ pub async fn handle(state: Parameters, action: Action) -> ActionResult {
             .await
             .contains(&direct_caller)
         {
+            let permissions = proxy_permissions(&direct_caller).await;
+
+            if let Some(permission) = required_proxy_permissions(&action)
+                .into_iter()
+                .find(|permission| !permissions.contains(permission))
+            {
+                return Err(ContractError::UnauthorizedProxyAction(permission));
+            }
+
             original_caller
         } else {
             direct_caller
@@ -1,6 +1,47 @@
+use std::cell::{Cell, RefCell};
+
 use crate::contract_utils::js_imports::SmartWeave;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use warp_erc1155::action::ForeignWrite;
+
+thread_local! {
+    /// Writes that weren't made because they happened during a simulation, `None` outside of one
+    static SIMULATED_WRITES: RefCell<Option<Vec<ForeignWrite>>> = const { RefCell::new(None) };
+
+    /// Number of writes made so far
+    static WRITES: Cell<u32> = const { Cell::new(0) };
+}
+
+pub fn writes() -> u32 {
+    WRITES.with(Cell::get)
+}
+
+/// Starts recording writes instead of making them, returns `false` if a simulation is already
+/// running
+pub fn start_simulation() -> bool {
+    SIMULATED_WRITES.with(|writes| {
+        let mut writes = writes.borrow_mut();
+
+        if writes.is_some() {
+            return false;
+        }
+
+        *writes = Some(Vec::new());
+        true
+    })
+}
+
+/// Stops recording writes and returns the ones that were recorded
+pub fn finish_simulation() -> Vec<ForeignWrite> {
+    SIMULATED_WRITES.with(|writes| writes.take().unwrap_or_default())
+}
+
+#[derive(Debug, Deserialize)]
+struct InternalWriteResult {
+    #[serde(rename = "type")]
+    result_type: String,
+}
 
 pub async fn read_foreign_contract_state<T: DeserializeOwned>(contract_address: &str) -> T {
     serde_wasm_bindgen::from_value(SmartWeave::read_contract_state(contract_address).await).unwrap()
@@ -19,3 +60,37 @@ pub async fn write_foreign_contract<T: DeserializeOwned, I: Serialize>(
     )
     .unwrap()
 }
+
+/// Like `write_foreign_contract` but only tells whether the write succeeded, in which case the
+/// result type is `ok`.
+///
+/// During a simulation, the write is only recorded and assumed to succeed.
+pub async fn try_write_foreign_contract<I: Serialize>(contract_address: &str, input: I) -> bool {
+    let simulated = SIMULATED_WRITES.with(|writes| {
+        writes.borrow_mut().as_mut().map(|writes| {
+            writes.push(ForeignWrite {
+                contract_id: contract_address.to_string(),
+                input: serde_json::to_value(&input).unwrap(),
+            })
+        })
+    });
+
+    if simulated.is_some() {
+        return true;
+    }
+
+    let Ok(input) = serde_wasm_bindgen::to_value(&input) else {
+        return false;
+    };
+
+    let succeeded = serde_wasm_bindgen::from_value::<InternalWriteResult>(
+        SmartWeave::write(contract_address, input).await,
+    )
+    .is_ok_and(|result| result.result_type == "ok");
+
+    if succeeded {
+        WRITES.with(|writes| writes.set(writes.get() + 1));
+    }
+
+    succeeded
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use crate::contract_utils::js_imports::Kv;
-use kv_storage::{kv, KvStorage};
+use crate::contract_utils::js_imports;
+use kv_storage::{kv, overlay::Overlay, KvStorage};
+use warp_erc1155::state::{ProxyPermission, TokenIdPolicy};
+
+/// Writes are kept in memory instead of being persisted while an action is simulated
+pub type Kv = Overlay<js_imports::Kv>;
 
 mod string {
     use std::fmt::Display;
@@ -29,7 +33,7 @@ mod string {
     }
 }
 
-pub type BalancePrecision = u64;
+pub type BalancePrecision = u128;
 
 #[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, Hash, PartialEq, Eq)]
 #[serde(transparent)]
@@ -42,6 +46,14 @@ impl Balance {
     pub fn new(value: BalancePrecision) -> Self {
         Self { value }
     }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.value.checked_add(other.value).map(Self::new)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.value.checked_sub(other.value).map(Self::new)
+    }
 }
 
 #[kv(impl = "Kv", subpath)]
@@ -58,6 +70,23 @@ pub struct Token {
     pub tx_id: Option<String>,
     #[kv(map)]
     pub balances: Balance,
+    pub max_supply: Option<Balance>,
+}
+
+/// Balance of an account before the first change made to it after a snapshot
+#[derive(Serialize, Deserialize, Copy, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotBalance {
+    pub snapshot_id: u32,
+    pub balance: Balance,
+}
+
+#[kv(impl = "Kv", subpath)]
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct BalanceSnapshots {
+    /// account -> balances sorted by snapshot id
+    #[kv(map)]
+    pub accounts: Vec<SnapshotBalance>,
 }
 
 #[kv(impl = "Kv", subpath)]
@@ -72,6 +101,8 @@ pub struct Settings {
     pub proxies: Vec<String>,
 
     pub allow_free_transfer: bool,
+
+    pub token_id_policy: TokenIdPolicy,
 }
 
 #[kv(impl = "Kv")]
@@ -84,4 +115,30 @@ pub struct State {
     pub approvals: Approvals,
     #[kv(subpath)]
     pub settings: Settings,
+    /// role -> addresses
+    #[kv(map)]
+    pub roles: Vec<String>,
+    /// Accounts that can't send or burn tokens
+    #[kv(map)]
+    pub frozen_accounts: bool,
+    /// Tokens that can't be transferred or burned
+    #[kv(map)]
+    pub paused_tokens: bool,
+    /// proxy -> permissions
+    #[kv(map)]
+    pub proxy_permissions: Vec<ProxyPermission>,
+    /// Contracts notified when they receive tokens
+    #[kv(map)]
+    pub receivers: bool,
+    /// Id of the last snapshot, 0 if none was taken
+    pub snapshot_id: u32,
+    /// snapshot id -> block height
+    #[kv(map)]
+    pub snapshots: u32,
+    /// Kept apart from `tokens` so that history survives tokens being burnt entirely
+    #[kv(map, subpath)]
+    pub balance_snapshots: BalanceSnapshots,
+    /// owner -> nonce of their next permit
+    #[kv(map)]
+    pub permit_nonces: u32,
 }
@@ -1,22 +1,406 @@
-use crate::state::State;
+use warp_common::validation::{find_duplicates, is_valid_key_segment, sorted};
+use warp_erc1155::{
+    error::{ContractError, InitialStateViolation},
+    state::{Balance as StateBalance, InitialState, Role, TokenIdPolicy},
+};
 
-pub async fn is_op(address: &str) -> bool {
-    State::settings()
-        .operators()
-        .get()
+use crate::state::{Balance, State, SubpathToken};
+
+/// Members that `role` is given by the legacy `super_operators` and `operators` lists.
+pub fn legacy_role_members(
+    role: Role,
+    super_operators: &[String],
+    operators: &[String],
+) -> Vec<String> {
+    let mut members = super_operators.to_vec();
+
+    if matches!(role, Role::Minter | Role::Burner | Role::Pauser) {
+        for operator in operators {
+            if !members.contains(operator) {
+                members.push(operator.clone());
+            }
+        }
+    }
+
+    members
+}
+
+/// Applies to `members` the change from `before` to `after` of the members that a role is given by
+/// the legacy lists, keeping the grants and revocations made since.
+pub fn apply_legacy_change(members: &mut Vec<String>, before: &[String], after: &[String]) {
+    members.retain(|member| !before.contains(member) || after.contains(member));
+
+    for member in after {
+        if !before.contains(member) && !members.contains(member) {
+            members.push(member.clone());
+        }
+    }
+}
+
+/// Contracts initialized before roles existed only have operator lists stored. Their roles are
+/// derived from these lists until they are first edited.
+pub async fn role_members(role: Role) -> Vec<String> {
+    if let Some(members) = State::roles(&role.to_string()).peek().await {
+        members
+    } else {
+        legacy_role_members(
+            role,
+            &State::settings().super_operators().get().await,
+            &State::settings().operators().get().await,
+        )
+    }
+}
+
+pub async fn has_role(role: Role, address: &str) -> bool {
+    role_members(role)
         .await
-        .contains(&address.into())
-        || State::settings()
-            .super_operators()
-            .get()
-            .await
-            .contains(&address.into())
+        .iter()
+        .any(|member| member == address)
 }
 
-pub async fn is_super_op(address: &str) -> bool {
+/// Contracts initialized before the token id policy existed don't have it stored, in which case
+/// the default one applies.
+pub async fn token_id_policy() -> TokenIdPolicy {
     State::settings()
-        .super_operators()
-        .get()
+        .token_id_policy()
+        .peek()
         .await
-        .contains(&address.into())
+        .unwrap_or_default()
+}
+
+/// Tokens minted before supply caps existed don't have `max_supply` stored, they are uncapped.
+pub async fn max_supply(token: &SubpathToken) -> Option<Balance> {
+    token.max_supply().peek().await.flatten()
+}
+
+/// Quantity of the token currently held by all of its owners
+pub async fn total_supply(token: &SubpathToken) -> Result<Balance, ContractError> {
+    token
+        .list_balances()
+        .await
+        .iter()
+        .try_fold(Balance::new(0), |supply, (_, balance)| {
+            supply.checked_add(*balance)
+        })
+        .ok_or(ContractError::BalanceOverflow)
+}
+
+/// Checks the shape of `token_id` against `policy`: its length and the characters it is made of.
+///
+/// Only ASCII characters are ever accepted, as KV ranges are bounded by `\x7f` when listing keys.
+pub fn validate_token_id(policy: &TokenIdPolicy, token_id: &str) -> Result<(), ContractError> {
+    let is_valid = !token_id.is_empty()
+        && token_id.len() <= policy.max_length as usize
+        && token_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || (c != '.' && policy.allowed_symbols.contains(c)));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ContractError::InvalidTokenId(token_id.to_string()))
+    }
+}
+
+pub fn is_token_id_reserved(policy: &TokenIdPolicy, token_id: &str) -> bool {
+    policy.reserved_prefixes.iter().any(|prefix| {
+        token_id
+            .strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+    })
+}
+
+/// Checks the tokens, balances and approvals of `init_state` and its settings' lists, ordered by
+/// token id and address.
+pub fn validate_initial_state(init_state: &InitialState) -> Vec<InitialStateViolation> {
+    let settings = &init_state.settings;
+    let mut violations = Vec::new();
+
+    if !init_state.tokens.contains_key(&settings.default_token) {
+        violations.push(InitialStateViolation::DefaultTokenNotFound(
+            settings.default_token.clone(),
+        ));
+    }
+
+    for (token_id, token) in sorted(&init_state.tokens) {
+        if validate_token_id(&settings.token_id_policy, token_id).is_err() {
+            violations.push(InitialStateViolation::InvalidTokenId(token_id.clone()));
+        }
+
+        for (address, balance) in sorted(&token.balances) {
+            if !is_valid_key_segment(address) {
+                violations.push(InitialStateViolation::InvalidAddress(address.clone()));
+            }
+
+            if balance.value == 0 {
+                violations.push(InitialStateViolation::ZeroBalance {
+                    token_id: token_id.clone(),
+                    address: address.clone(),
+                });
+            }
+        }
+
+        let supply = token
+            .balances
+            .values()
+            .try_fold(StateBalance::new(0), |supply, balance| {
+                supply.checked_add(*balance)
+            });
+
+        match (supply, token.max_supply) {
+            (None, _) => violations.push(InitialStateViolation::BalanceOverflow(token_id.clone())),
+            (Some(supply), Some(max_supply)) if supply.value > max_supply.value => {
+                violations.push(InitialStateViolation::MaxSupplyExceeded(token_id.clone()))
+            }
+            _ => {}
+        }
+    }
+
+    for (owner, approvals) in sorted(&init_state.approvals) {
+        let operators = sorted(&approvals.approves)
+            .into_iter()
+            .map(|(operator, _)| operator);
+
+        for address in std::iter::once(owner).chain(operators) {
+            if !is_valid_key_segment(address) {
+                violations.push(InitialStateViolation::InvalidAddress(address.clone()));
+            }
+        }
+    }
+
+    let lists = [
+        ("superOperators".to_string(), &settings.super_operators),
+        ("operators".to_string(), &settings.operators),
+        ("proxies".to_string(), &settings.proxies),
+    ]
+    .into_iter()
+    .chain(Role::ALL.iter().filter_map(|role| {
+        let members = settings.roles.get(role)?;
+        Some((format!("roles.{}", role), members))
+    }));
+
+    for (field, entries) in lists {
+        for address in find_duplicates(entries) {
+            violations.push(InitialStateViolation::DuplicateEntry {
+                field: field.clone(),
+                address: address.clone(),
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use warp_erc1155::state::{Approvals, Token};
+
+    use super::*;
+
+    const TX_ID: &str = "Xk_3-yhNbgTa8LpxSSQk3T5z-JXjZ8pTZ0lq4h2Wf_w";
+
+    fn policy() -> TokenIdPolicy {
+        TokenIdPolicy {
+            reserved_prefixes: vec!["SHUFFLE".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accepts_scarcity_nft_ids() {
+        for token_id in [
+            format!("1-UNIQUE-{}", TX_ID),
+            format!("10-LEGENDARY-{}", TX_ID),
+            format!("1000-RARE-{}", TX_ID),
+            format!("2-LIMITED-{}", TX_ID),
+            format!("SHUFFLE-{}", TX_ID),
+            "DOL".to_string(),
+        ] {
+            assert!(
+                validate_token_id(&policy(), &token_id).is_ok(),
+                "{}",
+                token_id
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_ids() {
+        for token_id in [
+            "",
+            "1-UNIQUE-a.b",
+            "1-UNIQUE-ab c",
+            "1-UNIQUE-é",
+            "1-UNIQUE-\x7f",
+            "1-UNIQUE-a/b",
+        ] {
+            assert!(
+                matches!(
+                    validate_token_id(&policy(), token_id),
+                    Err(ContractError::InvalidTokenId(id)) if id == token_id
+                ),
+                "{:?}",
+                token_id
+            );
+        }
+    }
+
+    #[test]
+    fn never_accepts_dots() {
+        let policy = TokenIdPolicy {
+            allowed_symbols: "-_.".to_string(),
+            ..policy()
+        };
+
+        assert!(validate_token_id(&policy, "a.b").is_err());
+    }
+
+    #[test]
+    fn enforces_max_length() {
+        let policy = TokenIdPolicy {
+            max_length: 8,
+            ..policy()
+        };
+
+        assert!(validate_token_id(&policy, "1-EPIC-a").is_ok());
+        assert!(validate_token_id(&policy, "1-EPIC-ab").is_err());
+    }
+
+    fn initial_state() -> InitialState {
+        let mut init_state = InitialState::default();
+        init_state.settings.default_token = "DOL".to_string();
+        init_state.settings.token_id_policy = policy();
+        init_state.tokens.insert(
+            "DOL".to_string(),
+            Token {
+                ticker: "DOL".to_string(),
+                balances: HashMap::from([("bank".to_string(), StateBalance::new(100))]),
+                ..Default::default()
+            },
+        );
+        init_state
+    }
+
+    #[test]
+    fn accepts_valid_initial_state() {
+        assert_eq!(validate_initial_state(&initial_state()), vec![]);
+    }
+
+    #[test]
+    fn lists_every_initial_state_violation() {
+        let mut init_state = initial_state();
+        init_state.settings.default_token = "EUR".to_string();
+        init_state.settings.operators = vec!["op".to_string(), "op".to_string(), "op".to_string()];
+        init_state.settings.roles.insert(
+            Role::Minter,
+            vec!["minter".to_string(), "minter".to_string()],
+        );
+        init_state.tokens.insert(
+            "a.b".to_string(),
+            Token {
+                ticker: "AB".to_string(),
+                balances: HashMap::from([
+                    ("alice".to_string(), StateBalance::new(0)),
+                    ("bob.eth".to_string(), StateBalance::new(10)),
+                ]),
+                max_supply: Some(StateBalance::new(5)),
+                ..Default::default()
+            },
+        );
+        init_state.approvals.insert(
+            "".to_string(),
+            Approvals {
+                approves: HashMap::from([("op".to_string(), true)]),
+            },
+        );
+
+        assert_eq!(
+            validate_initial_state(&init_state),
+            vec![
+                InitialStateViolation::DefaultTokenNotFound("EUR".to_string()),
+                InitialStateViolation::InvalidTokenId("a.b".to_string()),
+                InitialStateViolation::ZeroBalance {
+                    token_id: "a.b".to_string(),
+                    address: "alice".to_string(),
+                },
+                InitialStateViolation::InvalidAddress("bob.eth".to_string()),
+                InitialStateViolation::MaxSupplyExceeded("a.b".to_string()),
+                InitialStateViolation::InvalidAddress("".to_string()),
+                InitialStateViolation::DuplicateEntry {
+                    field: "operators".to_string(),
+                    address: "op".to_string(),
+                },
+                InitialStateViolation::DuplicateEntry {
+                    field: "roles.minter".to_string(),
+                    address: "minter".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_supply_overflows() {
+        let mut init_state = initial_state();
+        init_state
+            .tokens
+            .get_mut("DOL")
+            .unwrap()
+            .balances
+            .insert("whale".to_string(), StateBalance::new(u128::MAX));
+
+        assert_eq!(
+            validate_initial_state(&init_state),
+            vec![InitialStateViolation::BalanceOverflow("DOL".to_string())]
+        );
+    }
+
+    #[test]
+    fn migrates_operators_into_roles() {
+        let super_operators = vec!["super".to_string(), "both".to_string()];
+        let operators = vec!["op".to_string(), "both".to_string()];
+
+        for role in [Role::Minter, Role::Burner, Role::Pauser] {
+            assert_eq!(
+                legacy_role_members(role, &super_operators, &operators),
+                vec!["super", "both", "op"]
+            );
+        }
+
+        for role in [Role::Configurator, Role::Evolver, Role::ProxyAdmin] {
+            assert_eq!(
+                legacy_role_members(role, &super_operators, &operators),
+                vec!["super", "both"]
+            );
+        }
+    }
+
+    #[test]
+    fn applies_legacy_changes_only() {
+        let mut members = vec!["super".to_string(), "granted".to_string()];
+        let before = vec!["super".to_string(), "revoked".to_string()];
+        let after = vec!["revoked".to_string(), "added".to_string()];
+
+        apply_legacy_change(&mut members, &before, &after);
+        assert_eq!(members, vec!["granted", "added"]);
+    }
+
+    #[test]
+    fn detects_reserved_prefixes() {
+        assert!(is_token_id_reserved(&policy(), "SHUFFLE"));
+        assert!(is_token_id_reserved(
+            &policy(),
+            &format!("SHUFFLE-{}", TX_ID)
+        ));
+        assert!(!is_token_id_reserved(&policy(), "SHUFFLED"));
+        assert!(!is_token_id_reserved(
+            &policy(),
+            &format!("1-UNIQUE-{}", TX_ID)
+        ));
+        assert!(!is_token_id_reserved(
+            &policy(),
+            &format!("1-RARE-SHUFFLE-{}", TX_ID)
+        ));
+    }
 }
@@ -624,6 +624,10 @@ fn impl_kv_storage(ast: &syn::DeriveInput, macro_args: MacroArgs) -> TokenStream
                                     #kv_struct::get(&self.0).await.unwrap()
                                 }
 
+                                pub async fn peek(&self) -> Option<#field_type> {
+                                    #kv_struct::get::<#field_type>(&self.0).await
+                                }
+
                                 pub async fn set(&self, value: &#field_type) {
                                     #kv_struct::put::<#field_type>(&self.0, value).await;
                                 }
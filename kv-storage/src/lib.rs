@@ -3,6 +3,8 @@ use serde::{de::DeserializeOwned, Serialize};
 
 pub use kv_macro::kv_storage as kv;
 
+pub mod overlay;
+
 #[async_trait(?Send)]
 pub trait KvStorage {
     async fn put<T: Serialize>(key: &str, value: &T);
@@ -130,6 +132,14 @@ mod tests {
         }
     }
 
+    type SimulatedKv = crate::overlay::Overlay<Kv>;
+
+    #[kv(impl = "SimulatedKv")]
+    struct SimulatedState {
+        #[kv(map)]
+        balances: u32,
+    }
+
     #[kv(impl = "Kv", subpath)]
     struct Friend {
         #[kv(map)]
@@ -189,6 +199,80 @@ mod tests {
         println!("hello");
     }
 
+    #[tokio::test]
+    async fn overlay_never_reaches_storage() {
+        use crate::overlay::{self, KvChange};
+
+        SimulatedState::balances("alice").set(&10).await;
+        SimulatedState::balances("bob").set(&5).await;
+
+        overlay::start();
+
+        SimulatedState::balances("alice").set(&7).await;
+        SimulatedState::delete_balances("bob").await;
+        SimulatedState::balances("carol").set(&3).await;
+
+        assert_eq!(SimulatedState::balances("alice").peek().await, Some(7));
+        assert_eq!(SimulatedState::count_balances().await, 2);
+
+        let changes = overlay::finish::<Kv>().await;
+
+        assert_eq!(
+            changes,
+            vec![
+                KvChange {
+                    key: ".balances.alice".to_string(),
+                    before: Some(serde_json::json!(10)),
+                    after: Some(serde_json::json!(7)),
+                },
+                KvChange {
+                    key: ".balances.bob".to_string(),
+                    before: Some(serde_json::json!(5)),
+                    after: None,
+                },
+                KvChange {
+                    key: ".balances.carol".to_string(),
+                    before: None,
+                    after: Some(serde_json::json!(3)),
+                },
+            ]
+        );
+
+        assert!(!overlay::is_active());
+        assert_eq!(SimulatedState::balances("alice").peek().await, Some(10));
+        assert_eq!(SimulatedState::count_balances().await, 2);
+        assert!(!SimulatedState::balances("carol").exists().await);
+    }
+
+    #[tokio::test]
+    async fn overlay_layers_commit_and_rollback() {
+        use crate::overlay;
+
+        SimulatedState::balances("alice").set(&10).await;
+
+        overlay::start();
+        SimulatedState::balances("alice").set(&7).await;
+
+        overlay::start();
+        SimulatedState::balances("bob").set(&3).await;
+        overlay::rollback();
+
+        overlay::start();
+        SimulatedState::balances("carol").set(&3).await;
+        overlay::commit::<Kv>().await;
+
+        assert!(!SimulatedState::balances("bob").exists().await);
+        assert_eq!(SimulatedState::balances("carol").peek().await, Some(3));
+        assert_eq!(Kv::get::<u32>(".balances.alice").await, Some(10));
+
+        overlay::commit::<Kv>().await;
+
+        assert!(!overlay::is_active());
+        assert_eq!(Kv::get::<u32>(".balances.alice").await, Some(7));
+        assert_eq!(Kv::get::<u32>(".balances.carol").await, Some(3));
+        assert_eq!(Kv::get::<u32>(".balances.bob").await, None);
+    }
+
     #[tokio::test]
     async fn test_macro() {
         State {
@@ -0,0 +1,211 @@
+use std::{cell::RefCell, collections::BTreeMap, marker::PhantomData};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::KvStorage;
+
+/// Pending writes of a layer, `None` marks a deleted key
+type Layer = BTreeMap<String, Option<Value>>;
+
+thread_local! {
+    /// Stack of layers, the last one receiving the writes
+    static LAYERS: RefCell<Vec<Layer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A key modified in a layer, with its value before and after
+#[derive(Debug, Clone, PartialEq)]
+pub struct KvChange {
+    pub key: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Starts a new layer on top of the current ones, its writes are kept in memory until it's either
+/// committed or discarded
+pub fn start() {
+    LAYERS.with(|layers| layers.borrow_mut().push(Layer::new()));
+}
+
+pub fn is_active() -> bool {
+    LAYERS.with(|layers| !layers.borrow().is_empty())
+}
+
+fn pop() -> Layer {
+    LAYERS.with(|layers| layers.borrow_mut().pop().unwrap_or_default())
+}
+
+/// Discards the last layer
+pub fn rollback() {
+    pop();
+}
+
+/// Applies the writes of the last layer to the one below it, or to `K` if it was the only one
+pub async fn commit<K: KvStorage>() {
+    let writes = pop();
+
+    let writes = LAYERS.with(|layers| match layers.borrow_mut().last_mut() {
+        Some(parent) => {
+            parent.extend(writes);
+            None
+        }
+        None => Some(writes),
+    });
+
+    for (key, value) in writes.unwrap_or_default() {
+        match value {
+            Some(value) => K::put(&key, &value).await,
+            None => {
+                if K::get::<Value>(&key).await.is_some() {
+                    K::del(&key).await;
+                }
+            }
+        }
+    }
+}
+
+/// Discards the last layer and returns the keys it modified, sorted by key.
+///
+/// Keys written back to their original value are omitted.
+pub async fn finish<K: KvStorage>() -> Vec<KvChange> {
+    let writes = pop();
+
+    let mut changes = Vec::with_capacity(writes.len());
+
+    for (key, after) in writes {
+        let before = Overlay::<K>::get::<Value>(&key).await;
+
+        if before != after {
+            changes.push(KvChange { key, before, after });
+        }
+    }
+
+    changes
+}
+
+fn layers_get(key: &str) -> Option<Option<Value>> {
+    LAYERS.with(|layers| {
+        layers
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|layer| layer.get(key).cloned())
+    })
+}
+
+fn layers_set(key: &str, value: Option<Value>) {
+    LAYERS.with(|layers| {
+        if let Some(layer) = layers.borrow_mut().last_mut() {
+            layer.insert(key.to_string(), value);
+        }
+    });
+}
+
+fn in_range(key: &str, gte: Option<&str>, lt: Option<&str>) -> bool {
+    gte.iter().all(|gte| key >= *gte) && lt.iter().all(|lt| key < *lt)
+}
+
+/// Merges the layers on top of `K`'s entries in the given range
+async fn merged<K: KvStorage>(
+    gte: Option<&str>,
+    lt: Option<&str>,
+    reverse: Option<bool>,
+    limit: Option<u32>,
+) -> Vec<(String, Value)> {
+    let mut entries: BTreeMap<String, Value> = K::map::<Value>(gte, lt, None, None)
+        .await
+        .into_iter()
+        .collect();
+
+    LAYERS.with(|layers| {
+        for layer in layers.borrow().iter() {
+            for (key, value) in layer.iter() {
+                if !in_range(key, gte, lt) {
+                    continue;
+                }
+
+                match value {
+                    Some(value) => entries.insert(key.clone(), value.clone()),
+                    None => entries.remove(key),
+                };
+            }
+        }
+    });
+
+    let mut entries: Vec<(String, Value)> = entries.into_iter().collect();
+
+    if reverse.unwrap_or(false) {
+        entries.reverse();
+    }
+
+    if let Some(limit) = limit {
+        entries.truncate(limit as usize);
+    }
+
+    entries
+}
+
+/// Storage wrapper that behaves like `K` until a layer is started, then keeps every write in
+/// memory until the layers are committed.
+pub struct Overlay<K>(PhantomData<K>);
+
+#[async_trait(?Send)]
+impl<K: KvStorage> KvStorage for Overlay<K> {
+    async fn put<T: Serialize>(key: &str, value: &T) {
+        if is_active() {
+            layers_set(key, Some(serde_json::to_value(value).unwrap()));
+        } else {
+            K::put(key, value).await;
+        }
+    }
+
+    async fn del(key: &str) {
+        if is_active() {
+            layers_set(key, None);
+        } else {
+            K::del(key).await;
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(key: &str) -> Option<T> {
+        match layers_get(key) {
+            Some(value) => value.map(|value| serde_json::from_value(value).unwrap()),
+            None => K::get(key).await,
+        }
+    }
+
+    async fn keys(
+        gte: Option<&str>,
+        lt: Option<&str>,
+        reverse: Option<bool>,
+        limit: Option<u32>,
+    ) -> Vec<String> {
+        if !is_active() {
+            return K::keys(gte, lt, reverse, limit).await;
+        }
+
+        merged::<K>(gte, lt, reverse, limit)
+            .await
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    async fn map<T: DeserializeOwned>(
+        gte: Option<&str>,
+        lt: Option<&str>,
+        reverse: Option<bool>,
+        limit: Option<u32>,
+    ) -> Vec<(String, T)> {
+        if !is_active() {
+            return K::map(gte, lt, reverse, limit).await;
+        }
+
+        merged::<K>(gte, lt, reverse, limit)
+            .await
+            .into_iter()
+            .map(|(key, value)| (key, serde_json::from_value(value).unwrap()))
+            .collect()
+    }
+}
@@ -1,5 +1,6 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use warp_erc1155::action::{BatchActionResult, BatchMode, SimulationResult};
 use warp_erc1155::state::Balance;
 
 use crate::error::ContractError;
@@ -64,12 +65,30 @@ pub struct Evolve {
     pub value: String,
 }
 
+/// Handles `action` as if it was the interaction's input, without persisting anything, and reports
+/// what it would change
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulate {
+    pub action: Box<Action>,
+}
+
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Batch {
     pub actions: Vec<Action>,
 }
 
+/// Unlike `Batch`, reads and writes can be mixed and batches nested. The actions can't change the
+/// contract's parameters (e.g. `Evolve`) as the results are returned like a read's.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchV2 {
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "function")]
 pub enum Action {
@@ -80,15 +99,19 @@ pub enum Action {
     Unlock(Unlock),
     Configure(Configure),
     Evolve(Evolve),
+    Simulate(Simulate),
     Batch(Batch),
+    BatchV2(BatchV2),
 }
 
 #[derive(JsonSchema, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum ReadResponse {
     Batch(Vec<ReadResponse>),
+    BatchV2(Vec<BatchActionResult<ContractError, ReadResponse>>),
     GetVault((String, Vec<LockedBalance>)),
     GetAllVaults(Vec<(String, Vec<LockedBalance>)>),
+    Simulate(Box<SimulationResult<ContractError, ReadResponse>>),
 }
 
 #[derive(Serialize, Deserialize)]
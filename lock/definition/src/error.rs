@@ -28,8 +28,16 @@ pub enum ContractError {
     EvolveNotAllowed,
 
     ForbiddenNestedBatch,
+    /// `Initialize` can't be batched
+    ForbiddenActionInBatch,
+    ForbiddenNestedSimulation,
     CannotMixeReadAndWrite,
     EmptyBatch,
+    BatchActionFailed {
+        index: u32,
+        error: Box<ContractError>,
+    },
+    CannotChangeParametersInBatch,
 
     UnauthorizedConfiguration,
     UnauthorizedAddress(String),
@@ -55,4 +63,19 @@ pub enum ContractError {
     ContractIsPaused,
     ContractUninitialized,
     ContractAlreadyInitialized,
+    InvalidInitialState(Vec<InitialStateViolation>),
+}
+
+/// Reason for rejecting the initial state given to `Initialize`
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", content = "data")]
+pub enum InitialStateViolation {
+    /// Vault owners must be usable as KV key segments
+    InvalidAddress(String),
+    /// The locked balance at `index` in the vault of `owner` doesn't lock anything
+    ZeroQty { owner: String, index: u32 },
+    /// The locked balance at `index` in the vault of `owner` has more unlocked than it locked
+    UnlockedExceedsQty { owner: String, index: u32 },
+    /// An address listed more than once in `field`
+    DuplicateEntry { field: String, address: String },
 }
@@ -1,14 +1,29 @@
 use async_trait::async_trait;
+use kv_storage::overlay;
+use warp_erc1155::action::{BatchActionResult, BatchMode, StateChange};
 use warp_lock::{
-    action::{Action, ActionResult, Batch, HandlerResult, ReadResponse},
+    action::{Action, ActionResult, Batch, BatchV2, HandlerResult, ReadResponse},
     error::ContractError,
     state::Parameters,
 };
 
-use crate::{contract::handle, contract_utils::foreign_call::ForeignContractCaller};
+use crate::{
+    contract::handle,
+    contract_utils::{foreign_call::ForeignContractCaller, js_imports},
+};
 
 use super::AsyncActionable;
 
+/// Checks that `action` can be part of a batch, the actions that must be run at the top level
+/// can't.
+fn check_batchable(action: &Action) -> Result<(), ContractError> {
+    match action {
+        Action::Batch(_) | Action::BatchV2(_) => Err(ContractError::ForbiddenNestedBatch),
+        Action::Initialize(_) => Err(ContractError::ForbiddenActionInBatch),
+        _ => Ok(()),
+    }
+}
+
 #[async_trait(?Send)]
 impl AsyncActionable for Batch {
     async fn action(
@@ -24,9 +39,7 @@ impl AsyncActionable for Batch {
         let mut none_mode = false;
 
         for action in self.actions {
-            if let Action::Batch(_) = action {
-                return Err(ContractError::ForbiddenNestedBatch);
-            }
+            check_batchable(&action)?;
 
             state = match handle(state, action, foreign_caller).await? {
                 HandlerResult::Write(state) => {
@@ -67,3 +80,81 @@ impl AsyncActionable for Batch {
         }
     }
 }
+
+/// Handles one of a `BatchV2`'s actions and returns its response, if it's a read
+async fn handle_in_batch(
+    action: Action,
+    state: &Parameters,
+    foreign_caller: &mut ForeignContractCaller,
+) -> Result<Option<ReadResponse>, ContractError> {
+    let (new_state, response) = match handle(state.clone(), action, foreign_caller).await? {
+        HandlerResult::Write(new_state) | HandlerResult::None(new_state) => (new_state, None),
+        HandlerResult::Read(new_state, response) => (new_state, Some(response)),
+    };
+
+    if !StateChange::from_parameters(state, &new_state).is_empty() {
+        return Err(ContractError::CannotChangeParametersInBatch);
+    }
+
+    Ok(response)
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for BatchV2 {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        if self.actions.is_empty() {
+            return Err(ContractError::EmptyBatch);
+        }
+
+        for action in &self.actions {
+            check_batchable(action)?;
+        }
+
+        let mut results = Vec::with_capacity(self.actions.len());
+
+        for (index, action) in self.actions.into_iter().enumerate() {
+            let writes = foreign_caller.writes();
+
+            let result = match self.mode {
+                // Failing the whole interaction rolls back the writes of the previous actions
+                BatchMode::Atomic => handle_in_batch(action, &state, foreign_caller).await,
+                BatchMode::BestEffort => {
+                    overlay::start();
+
+                    let result = handle_in_batch(action, &state, foreign_caller).await;
+
+                    if result.is_ok() {
+                        overlay::commit::<js_imports::Kv>().await;
+                    } else {
+                        overlay::rollback();
+                    }
+
+                    result
+                }
+            };
+
+            results.push(match result {
+                Ok(Some(response)) => BatchActionResult::Read(response),
+                Ok(None) => BatchActionResult::Write,
+                // Writes to other contracts can't be rolled back on their own, the interaction
+                // has to fail for them to be
+                Err(error)
+                    if self.mode == BatchMode::Atomic || foreign_caller.writes() != writes =>
+                {
+                    return Err(ContractError::BatchActionFailed {
+                        index: index as u32,
+                        error: Box::new(error),
+                    });
+                }
+                Err(error) => BatchActionResult::Error(error),
+            });
+        }
+
+        Ok(HandlerResult::Read(state, ReadResponse::BatchV2(results)))
+    }
+}
@@ -2,16 +2,66 @@ use async_trait::async_trait;
 
 use warp_lock::{
     action::{ActionResult, HandlerResult, Initialize},
-    error::ContractError,
-    state::Parameters,
+    error::{ContractError, InitialStateViolation},
+    state::{InitialState, LockedBalance, Parameters},
 };
 
+use warp_common::validation::{find_duplicates, is_valid_key_segment, sorted};
+
 use crate::{
     actions::AsyncActionable,
     contract_utils::foreign_call::ForeignContractCaller,
     state::{Settings, State},
 };
 
+/// Checks the locked balances of `init_state`, ordered by owner, then its settings.
+fn validate_initial_state(init_state: &InitialState) -> Vec<InitialStateViolation> {
+    let mut violations = Vec::new();
+
+    for (owner, balances) in sorted(&init_state.vault) {
+        if !is_valid_key_segment(owner) {
+            violations.push(InitialStateViolation::InvalidAddress(owner.clone()));
+        }
+
+        for (index, balance) in balances.iter().enumerate() {
+            let (qty, unlocked) = match balance {
+                LockedBalance::Cliff(cliff) => (cliff.qty.value, 0),
+                LockedBalance::Linear(linear) => (linear.qty.value, linear.unlocked.value),
+            };
+
+            if qty == 0 {
+                violations.push(InitialStateViolation::ZeroQty {
+                    owner: owner.clone(),
+                    index: index as u32,
+                });
+            }
+
+            if unlocked > qty {
+                violations.push(InitialStateViolation::UnlockedExceedsQty {
+                    owner: owner.clone(),
+                    index: index as u32,
+                });
+            }
+        }
+    }
+
+    let settings = &init_state.settings;
+
+    for (field, entries) in [
+        ("superOperators", &settings.super_operators),
+        ("operators", &settings.operators),
+    ] {
+        for address in find_duplicates(entries) {
+            violations.push(InitialStateViolation::DuplicateEntry {
+                field: field.to_string(),
+                address: address.clone(),
+            });
+        }
+    }
+
+    violations
+}
+
 #[async_trait(?Send)]
 impl AsyncActionable for Initialize {
     async fn action(
@@ -21,6 +71,12 @@ impl AsyncActionable for Initialize {
         _foreign_caller: &mut ForeignContractCaller,
     ) -> ActionResult {
         if let Some(init_state) = parameters.initial_state {
+            let violations = validate_initial_state(&init_state);
+
+            if !violations.is_empty() {
+                return Err(ContractError::InvalidInitialState(violations));
+            }
+
             let state = State {
                 settings: Settings {
                     paused: init_state.settings.paused,
@@ -8,6 +8,7 @@ pub mod evolve;
 pub mod get_all_vaults;
 pub mod get_vault;
 pub mod initialize;
+pub mod simulate;
 pub mod transfer_locked;
 pub mod unlock;
 
@@ -14,7 +14,10 @@ use crate::{
 };
 
 pub fn is_action_read(action: &Action) -> bool {
-    matches!(action, Action::GetVault(_) | Action::GetAllVaults(_))
+    matches!(
+        action,
+        Action::GetVault(_) | Action::GetAllVaults(_) | Action::Simulate(_)
+    )
 }
 
 pub fn allowed_in_pause(action: &Action) -> bool {
@@ -60,6 +63,8 @@ pub async fn handle(
         Action::Unlock(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::Configure(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::Evolve(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::Simulate(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::Batch(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::BatchV2(action) => action.action(direct_caller, state, foreign_caller).await,
     }
 }
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use kv_storage::{kv, KvStorage};
+use kv_storage::{kv, overlay::Overlay, KvStorage};
 
 use warp_lock::state::LockedBalance;
 
-use crate::contract_utils::js_imports::Kv;
+use crate::contract_utils::js_imports;
+
+/// Writes are kept in memory instead of being persisted while an action is simulated
+pub type Kv = Overlay<js_imports::Kv>;
 
 #[kv(impl = "Kv", subpath)]
 pub struct Settings {
@@ -1,10 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use warp_erc1155::action::{BatchActionResult, BatchMode, SimulationResult};
 use warp_erc1155::state::Balance;
 
 use crate::error::ContractError;
-use crate::state::{AttachedRoyalties, Parameters, Royalties};
+use crate::state::{
+    AttachedRoyalties, Auction, Credit, LazyDrop, Listing, Offer, Parameters, Royalties, Sale,
+    ScarcityTier,
+};
 
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,6 +24,22 @@ pub struct GetRoyalties {
 #[serde(rename_all = "camelCase")]
 pub struct GetAllRoyalties;
 
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSalesHistory {
+    pub token_id: String,
+    /// Only sales recorded after the one at this index are returned
+    pub after: Option<u32>,
+    /// Defaults to 100, at most 1000
+    pub limit: Option<u32>,
+}
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLastSale {
+    pub token_id: String,
+}
+
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AttachRoyalties {
@@ -43,77 +63,263 @@ pub struct RemoveAttachedRoyalties {
 }
 
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
-pub struct LimitedAmount {
-    #[serde(skip_serializing_if = "Option::is_none")]
+#[serde(rename_all = "camelCase")]
+pub struct Scarcity {
+    /// Name of one of the tiers of the settings, case insensitive
+    pub scarcity: String,
+    /// Editions to mint instead of the tier's count, within its maximum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub amount: Option<u32>,
 }
 
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", tag = "scarcity")]
-pub enum Scarcity {
-    Unique,
-    Legendary,
-    Epic,
-    Rare,
-    Limited(LimitedAmount),
+#[serde(rename_all = "camelCase")]
+pub struct MintNft {
+    pub scarcity: Scarcity,
+    pub base_id: Option<String>,
+    pub royalties: Royalties,
+    pub rate: u32,
+    /// Token that the NFTs are sold for unless a sale specifies one, which must be allowed by the
+    /// settings
+    pub payment_token: Option<String>,
+    /// Records the drop instead of minting its editions, each of them being minted when it is
+    /// first sold through `Transfer`. Restricted to operators.
+    #[serde(default)]
+    pub lazy: bool,
 }
 
-impl TryFrom<&str> for Scarcity {
-    type Error = ();
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLazyDrop {
+    pub base_id: String,
+}
 
-    fn try_from(scarcity_raw: &str) -> Result<Self, Self::Error> {
-        match scarcity_raw.to_uppercase().as_str() {
-            "UNIQUE" => Ok(Self::Unique),
-            "LEGENDARY" => Ok(Self::Legendary),
-            "EPIC" => Ok(Self::Epic),
-            "RARE" => Ok(Self::Rare),
-            "LIMITED" => Ok(Self::Limited(LimitedAmount { amount: None })),
-            _ => Err(()),
-        }
-    }
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    pub from: String,
+    pub target: String,
+    pub token_id: String,
+    pub price: Balance,
+    pub qty: Option<Balance>,
+    /// Token the price is paid with, which must be allowed by the settings. Defaults to the
+    /// payment token of the NFT, or to the ERC1155 contract's default token if it has none.
+    pub payment_token: Option<String>,
 }
 
-impl ToString for Scarcity {
-    fn to_string(&self) -> String {
-        match self {
-            Scarcity::Unique => "UNIQUE".to_string(),
-            Scarcity::Legendary => "LEGENDARY".to_string(),
-            Scarcity::Epic => "EPIC".to_string(),
-            Scarcity::Rare => "RARE".to_string(),
-            Scarcity::Limited(_) => "LIMITED".to_string(),
-        }
-    }
+/// Computes what a `Transfer` with the same fields would pay and move, without executing it
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteTransfer {
+    pub from: String,
+    pub target: String,
+    pub token_id: String,
+    pub price: Balance,
+    pub qty: Option<Balance>,
+    pub payment_token: Option<String>,
 }
 
-impl From<&Scarcity> for Option<u32> {
-    fn from(scarcity: &Scarcity) -> Self {
-        match scarcity {
-            Scarcity::Unique => Some(1),
-            Scarcity::Legendary => Some(10),
-            Scarcity::Epic => Some(100),
-            Scarcity::Rare => Some(1000),
-            Scarcity::Limited(LimitedAmount { amount }) => *amount,
+impl From<QuoteTransfer> for Transfer {
+    fn from(quote: QuoteTransfer) -> Self {
+        Self {
+            from: quote.from,
+            target: quote.target,
+            token_id: quote.token_id,
+            price: quote.price,
+            qty: quote.qty,
+            payment_token: quote.payment_token,
         }
     }
 }
 
-#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[derive(JsonSchema, Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct MintNft {
-    pub scarcity: Scarcity,
-    pub base_id: Option<String>,
-    pub royalties: Royalties,
-    pub rate: u32,
+pub enum PayoutRole {
+    /// Owner of the token, who also gets the rounding dust of the royalties
+    Seller,
+    Shareholder,
 }
 
+/// Amount of the payment token that the buyer pays to `address`
 #[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Transfer {
+pub struct Payout {
+    pub address: String,
+    pub amount: Balance,
+    pub role: PayoutRole,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMovement {
     pub from: String,
     pub target: String,
+    pub token_id: String,
+    pub qty: Balance,
+    /// Whether the token is an edition of a lazy drop that gets minted to `target` instead of
+    /// being transferred
+    pub minted: bool,
+}
+
+#[derive(JsonSchema, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferQuote {
+    pub is_resell: bool,
+    /// Token the price is paid with, the ERC1155 contract's default token if missing
+    pub payment_token: Option<String>,
+    /// Payments from the buyer, empty payouts and the ones the buyer would make to themselves
+    /// being left out
+    pub payouts: Vec<Payout>,
+    pub token_movement: TokenMovement,
+}
+
+/// Puts an NFT of the caller up for sale, replacing its previous listing.
+///
+/// NOTE: The seller must have approved this contract in the ERC1155 contract for the NFT to be
+/// bought.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct List {
     pub token_id: String,
     pub price: Balance,
-    pub qty: Option<Balance>,
+    pub payment_token: Option<String>,
+    /// Block height from which the NFT can't be bought anymore
+    pub expires_at: Option<u32>,
+}
+
+/// Removes the listing of an NFT, which only its seller and the operators can do
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delist {
+    pub token_id: String,
+}
+
+/// Buys a listed NFT for the caller, paying its seller and its shareholders like `Transfer`.
+///
+/// NOTE: The buyer must have approved this contract in the ERC1155 contract.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Buy {
+    pub token_id: String,
+}
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetListing {
+    pub token_id: String,
+}
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSellerListings {
+    pub seller: String,
+}
+
+/// Offers a price for an NFT, which is moved from the caller to this contract until the offer is
+/// accepted or cancelled.
+///
+/// NOTE: The bidder must have approved this contract in the ERC1155 contract.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MakeOffer {
+    pub token_id: String,
+    pub price: Balance,
+    pub payment_token: Option<String>,
+    /// Block height from which the offer can't be accepted anymore
+    pub expires_at: Option<u32>,
+}
+
+/// Gives the price of an offer back to its bidder, which only the bidder and the operators can do
+/// until the offer expires
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOffer {
+    pub offer_id: String,
+}
+
+/// Sells the NFT of the caller to the bidder of an offer, paying the caller and the shareholders
+/// like `Transfer`.
+///
+/// NOTE: The caller must have approved this contract in the ERC1155 contract unless the NFT is
+/// listed.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptOffer {
+    pub offer_id: String,
+}
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTokenOffers {
+    pub token_id: String,
+}
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBidderOffers {
+    pub bidder: String,
+}
+
+/// Puts an NFT of the caller up for auction, moving it to this contract until the auction is
+/// settled.
+///
+/// NOTE: The seller must have approved this contract in the ERC1155 contract.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAuction {
+    pub token_id: String,
+    pub reserve_price: Balance,
+    pub payment_token: Option<String>,
+    pub start_height: u32,
+    pub end_height: u32,
+    pub min_increment: Balance,
+}
+
+/// Outbids the highest bid of an auction, whose amount is credited to its bidder, see `Withdraw`.
+///
+/// Bids made less than `AUCTION_EXTENSION` blocks before the end of the auction push it back.
+///
+/// NOTE: The bidder must have approved this contract in the ERC1155 contract.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bid {
+    pub token_id: String,
+    pub amount: Balance,
+}
+
+/// Sells the NFT of an ended auction to its highest bidder, paying its seller and its
+/// shareholders like `Transfer`, or gives it back to its seller if nobody bid
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleAuction {
+    pub token_id: String,
+}
+
+/// Gives the NFT of an auction back to its seller and credits its highest bid to its bidder.
+///
+/// Sellers may only cancel their auctions that nobody bid on, operators may cancel any auction,
+/// e.g. one that can't be settled anymore.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelAuction {
+    pub token_id: String,
+}
+
+/// Sends the caller the amounts credited to them, such as their outbid bids
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdraw;
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCredits {
+    pub account: String,
+}
+
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAuction {
+    pub token_id: String,
 }
 
 #[derive(JsonSchema, Debug, Serialize, Deserialize, Default)]
@@ -124,6 +330,8 @@ pub struct Configure {
     pub operators: Option<Vec<String>>,
     pub erc1155: Option<String>,
     pub custodian: Option<String>,
+    pub scarcity_tiers: Option<Vec<ScarcityTier>>,
+    pub payment_tokens: Option<Vec<String>>,
     pub can_evolve: Option<bool>,
 }
 
@@ -133,12 +341,30 @@ pub struct Evolve {
     pub value: String,
 }
 
+/// Handles `action` as if it was the interaction's input, without persisting anything, and reports
+/// what it would change
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulate {
+    pub action: Box<Action>,
+}
+
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Batch {
     pub actions: Vec<Action>,
 }
 
+/// Unlike `Batch`, reads and writes can be mixed and batches nested. The actions can't change the
+/// contract's parameters (e.g. `Evolve`) as the results are returned like a read's.
+#[derive(JsonSchema, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchV2 {
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
 #[derive(JsonSchema, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "function")]
 pub enum Action {
@@ -149,10 +375,33 @@ pub enum Action {
     EditAttachedRoyalties(EditAttachedRoyalties),
     RemoveAttachedRoyalties(RemoveAttachedRoyalties),
     MintNft(MintNft),
+    GetLazyDrop(GetLazyDrop),
+    GetSalesHistory(GetSalesHistory),
+    GetLastSale(GetLastSale),
     Transfer(Transfer),
+    QuoteTransfer(QuoteTransfer),
+    List(List),
+    Delist(Delist),
+    Buy(Buy),
+    GetListing(GetListing),
+    GetSellerListings(GetSellerListings),
+    MakeOffer(MakeOffer),
+    CancelOffer(CancelOffer),
+    AcceptOffer(AcceptOffer),
+    GetTokenOffers(GetTokenOffers),
+    GetBidderOffers(GetBidderOffers),
+    CreateAuction(CreateAuction),
+    Bid(Bid),
+    SettleAuction(SettleAuction),
+    CancelAuction(CancelAuction),
+    GetAuction(GetAuction),
+    Withdraw(Withdraw),
+    GetCredits(GetCredits),
     Configure(Configure),
     Evolve(Evolve),
+    Simulate(Simulate),
     Batch(Batch),
+    BatchV2(BatchV2),
 }
 
 #[derive(JsonSchema, Serialize, Deserialize, Debug)]
@@ -160,7 +409,35 @@ pub enum Action {
 pub enum ReadResponse {
     GetRoyalties((String, AttachedRoyalties)),
     GetAllRoyalties(Vec<(String, AttachedRoyalties)>),
+    GetLazyDrop(LazyDrop),
+    #[serde(rename_all = "camelCase")]
+    GetSalesHistory {
+        token_id: String,
+        /// Sales from the oldest to the most recent, with their index
+        sales: Vec<(u32, Sale)>,
+        /// Total number of sales of the token
+        sales_count: u32,
+        /// Cursor to pass as `after` to get the next page, if there is one
+        next: Option<u32>,
+    },
+    GetLastSale(Option<Sale>),
+    QuoteTransfer(TransferQuote),
+    /// Missing if the NFT isn't listed or if its listing expired
+    GetListing(Option<Listing>),
+    /// Listings that haven't expired
+    GetSellerListings(Vec<Listing>),
+    /// Offers from the oldest to the most recent, including the expired ones that weren't
+    /// cancelled yet
+    GetTokenOffers(Vec<Offer>),
+    /// Offers from the oldest to the most recent, including the expired ones that weren't
+    /// cancelled yet
+    GetBidderOffers(Vec<Offer>),
+    GetAuction(Option<Auction>),
+    /// Amounts that the account can withdraw, one per payment token
+    GetCredits(Vec<Credit>),
+    Simulate(Box<SimulationResult<ContractError, ReadResponse>>),
     Batch(Vec<ReadResponse>),
+    BatchV2(Vec<BatchActionResult<ContractError, ReadResponse>>),
 }
 
 #[derive(Serialize, Deserialize)]
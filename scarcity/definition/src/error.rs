@@ -1,6 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use warp_erc1155::state::Balance;
+
 #[derive(JsonSchema, Serialize, Deserialize, Debug)]
 #[serde(tag = "kind", content = "data")]
 pub enum ForeignReadError {
@@ -28,8 +30,16 @@ pub enum ContractError {
     EvolveNotAllowed,
 
     ForbiddenNestedBatch,
+    /// `Initialize` can't be batched
+    ForbiddenActionInBatch,
+    ForbiddenNestedSimulation,
     CannotMixeReadAndWrite,
     EmptyBatch,
+    BatchActionFailed {
+        index: u32,
+        error: Box<ContractError>,
+    },
+    CannotChangeParametersInBatch,
 
     UnauthorizedConfiguration,
     UnauthorizedAddress(String),
@@ -44,6 +54,33 @@ pub enum ContractError {
     TokenDoesNotExist(String),
     TokenIsNotAnNFT(String),
     InvalidTokenId,
+    UnknownScarcity(String),
+    InvalidScarcityTier(String),
+    /// Tiers of the settings must be kept when configuring new ones
+    ScarcityTierRemoved(String),
+    InvalidEditionsCount(u32),
+    EditionsCountRequired(String),
+    LazyDropNotFound(String),
+    ListingNotFound(String),
+    ListingExpired(String),
+    InvalidExpiration(u32),
+    OfferNotFound(String),
+    OfferExpired(String),
+    TokenIsListed(String),
+    TokenIsAuctioned(String),
+    InvalidAuctionPeriod,
+    AuctionNotFound(String),
+    AuctionNotStarted(String),
+    AuctionEnded(String),
+    AuctionNotEnded(String),
+    /// Sellers can't cancel the auctions that were bid on
+    AuctionHasBids(String),
+    NothingToWithdraw,
+    /// Bids must be at least this amount
+    BidTooLow(Balance),
+    PaymentTokenNotAllowed(String),
+    /// Requested page size, which must be within 1 and 1000
+    InvalidLimit(u32),
 
     TransferResult(String),
 
@@ -58,6 +95,32 @@ pub enum ContractError {
     ContractIsPaused,
     ContractUninitialized,
     ContractAlreadyInitialized,
+    InvalidInitialState(Vec<InitialStateViolation>),
     RoyaltiesNotChanged,
     RoyaltiesUnchanged,
 }
+
+/// Reason for rejecting the initial state given to `Initialize`
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", content = "data")]
+pub enum InitialStateViolation {
+    /// Not usable as a KV key segment, see `InvalidAddress` in the ERC1155 contract
+    InvalidBaseId(String),
+    /// The `baseId` of attached royalties differs from the id they are attached to
+    #[serde(rename_all = "camelCase")]
+    BaseIdMismatch {
+        id: String,
+        base_id: String,
+    },
+    InvalidRate(String),
+    /// The royalties attached to this base id don't sum up to `UNIT`
+    InvalidRoyalties(String),
+    InvalidScarcityTier(String),
+    /// The payment token of the royalties attached to this base id isn't allowed by the settings
+    PaymentTokenNotAllowed(String),
+    /// An address listed more than once in `field`
+    DuplicateEntry {
+        field: String,
+        address: String,
+    },
+}
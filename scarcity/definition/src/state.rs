@@ -3,10 +3,16 @@ use std::collections::HashMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use warp_erc1155::state::Balance;
+
 // TODO: Find a way to export `UNIT` via schemars or put it in `Settings`.
 /// The exact amount that all the sum of all the royalties of a token must be equal to.
 pub const UNIT: u32 = 1_000_000;
 
+/// Blocks that a bid made near the end of an auction leaves to the other bidders, the auction
+/// being extended if needed.
+pub const AUCTION_EXTENSION: u32 = 5;
+
 /**
  * address -> share
  */
@@ -18,19 +24,49 @@ pub struct AttachedRoyalties {
     pub base_id: String,
     pub royalties: Royalties,
     pub rate: u32,
-    // NOTE: The following will only be necessary when artists will be allowed to mint instead of
-    // Pianity. At the moment Pianity is acting as the custodian so we can just check for the
-    // `custodian` field in the contract's settings.
-    //
-    // /// Address of the original owner of the NFT
-    // ///
-    // /// NOTE: This is required because in order to determine whether a transfer is a sell or a
-    // /// resell, we have to know who originally owned the NFT.
-    // ///
-    // /// In theory this should be obtained by simply checking what's the address that minted the NFT
-    // /// however it isn't possible without using the unsafe client so we resort to manually storing
-    // /// the address.
-    // pub minter: String,
+    /// Address that minted the NFT and originally owned it, unknown for the NFTs minted before
+    /// artists were allowed to mint and for royalties attached to existing tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minter: Option<String>,
+    /// Token that the NFTs are sold for unless a sale specifies one, the ERC1155 contract's
+    /// default token if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_token: Option<String>,
+}
+
+/// A level of scarcity that NFTs can be minted with
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScarcityTier {
+    /// Upper-case name used in the ids of the NFTs, e.g. `LEGENDARY` in `1-LEGENDARY-<baseId>`
+    pub name: String,
+    /// Editions minted when `MintNft` doesn't specify an amount, which tiers without maximum
+    /// require
+    pub editions: u32,
+    /// Editions that an NFT of this tier can have at most, unbounded if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_editions: Option<u32>,
+}
+
+impl ScarcityTier {
+    pub fn new(name: &str, editions: u32, max_editions: Option<u32>) -> Self {
+        Self {
+            name: name.to_string(),
+            editions,
+            max_editions,
+        }
+    }
+}
+
+/// Tiers of the contracts initialized before tiers were configurable
+pub fn default_scarcity_tiers() -> Vec<ScarcityTier> {
+    vec![
+        ScarcityTier::new("UNIQUE", 1, Some(1)),
+        ScarcityTier::new("LEGENDARY", 10, Some(10)),
+        ScarcityTier::new("EPIC", 100, Some(100)),
+        ScarcityTier::new("RARE", 1000, Some(1000)),
+        ScarcityTier::new("LIMITED", 1, None),
+    ]
 }
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
@@ -44,13 +80,132 @@ pub struct Settings {
     /// Address of the attached ERC1155-compliant contract
     pub erc1155: String,
 
-    /// NOTE: Currently only Pianity is allowed to do mints and transfers which means that
-    /// ownership always defaults to Pianity. This field represents the address to which ownership
-    /// always defaults in the ERC1155 contract.
+    /// Address to which the ownership of the NFTs minted by Pianity defaults in the ERC1155
+    /// contract.
     ///
-    /// It is required in order to, for example, determine whether a transfer represents a sell or
-    /// a resell.
+    /// NOTE: It is only used to determine whether a transfer represents a sell or a resell for the
+    /// NFTs whose minter is unknown, the first sale of the others being tracked.
     pub custodian: String,
+
+    /// NOTE: Tiers can be added but not removed, their NFTs being transferable only while their
+    /// tier is listed.
+    #[serde(default = "default_scarcity_tiers")]
+    pub scarcity_tiers: Vec<ScarcityTier>,
+
+    /// Tokens of the ERC1155 contract that NFTs may be sold for besides its default token
+    ///
+    /// NOTE: The listings, offers and auctions in a token that is removed can't be settled anymore,
+    /// only delisted or cancelled.
+    #[serde(default)]
+    pub payment_tokens: Vec<String>,
+}
+
+/// NFTs whose editions are only minted in the ERC1155 contract when they are first sold
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LazyDrop {
+    pub base_id: String,
+    /// Name of the scarcity tier
+    pub scarcity: String,
+    pub editions: u32,
+    /// Editions minted so far
+    pub minted: u32,
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SaleKind {
+    /// First sale of the token, whose price goes to its shareholders
+    #[default]
+    Primary,
+    /// Resell, whose price goes to the seller minus the royalties
+    Secondary,
+}
+
+/// Provenance record of a token moved by `Transfer`
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Sale {
+    pub from: String,
+    pub to: String,
+    pub qty: Balance,
+    pub price: Balance,
+    /// Token the price was paid with, the ERC1155 contract's default token if missing
+    pub payment_token: Option<String>,
+    pub block_height: u32,
+    pub tx_id: String,
+    pub kind: SaleKind,
+}
+
+/// NFT put up for sale at a fixed price by its owner
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    pub token_id: String,
+    pub seller: String,
+    pub price: Balance,
+    /// Token the price is paid with, the ERC1155 contract's default token if missing
+    pub payment_token: Option<String>,
+    /// Block height from which the NFT can't be bought anymore, never if missing
+    pub expires_at: Option<u32>,
+    /// Whether this contract holds the NFT until it's bought or delisted, which isn't the case of
+    /// the editions of lazy drops that aren't minted yet
+    pub escrowed: bool,
+}
+
+/// Price offered for an NFT, which this contract holds until the offer is accepted or cancelled
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Offer {
+    /// Id of the transaction that made the offer, followed by `-<n>` if the transaction made
+    /// other offers before it
+    pub id: String,
+    pub token_id: String,
+    pub bidder: String,
+    pub price: Balance,
+    /// Token the price is paid with, the ERC1155 contract's default token if missing
+    pub payment_token: Option<String>,
+    /// Block height from which the offer can't be accepted anymore, never if missing
+    pub expires_at: Option<u32>,
+}
+
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuctionBid {
+    pub bidder: String,
+    pub amount: Balance,
+}
+
+/// Amount held by this contract for an account, which it withdraws with `Withdraw`
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Credit {
+    /// The ERC1155 contract's default token if missing
+    pub payment_token: Option<String>,
+    pub amount: Balance,
+}
+
+/// English auction of an NFT, whose highest bid this contract holds until it's outbid or the
+/// auction is settled
+#[derive(JsonSchema, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Auction {
+    pub token_id: String,
+    pub seller: String,
+    /// Minimum amount of the first bid
+    pub reserve_price: Balance,
+    /// Minimum difference between a bid and the one it outbids
+    pub min_increment: Balance,
+    /// Token the bids are paid with, the ERC1155 contract's default token if missing
+    pub payment_token: Option<String>,
+    /// Block height from which bids are accepted
+    pub start_height: u32,
+    /// Block height from which bids aren't accepted anymore, pushed back by late bids
+    pub end_height: u32,
+    /// Whether this contract holds the NFT until the auction is settled, which isn't the case of
+    /// the editions of lazy drops that aren't minted yet
+    pub escrowed: bool,
+    pub highest_bid: Option<AuctionBid>,
 }
 
 #[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
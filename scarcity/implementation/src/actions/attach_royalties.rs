@@ -13,6 +13,8 @@ use crate::{
 
 pub async fn attach_royalties_internal(
     attach_royalties: &AttachRoyalties,
+    minter: Option<String>,
+    payment_token: Option<String>,
 ) -> Result<(), ContractError> {
     if attach_royalties.rate > UNIT {
         return Err(ContractError::InvalidRate);
@@ -35,6 +37,8 @@ pub async fn attach_royalties_internal(
             base_id: attach_royalties.base_id.clone(),
             royalties: attach_royalties.royalties.clone(),
             rate: attach_royalties.rate,
+            minter,
+            payment_token,
         })
         .await;
 
@@ -53,7 +57,7 @@ impl AsyncActionable for AttachRoyalties {
             return Err(ContractError::TokenAlreadyExists(self.base_id));
         }
 
-        attach_royalties_internal(&self).await?;
+        attach_royalties_internal(&self, None, None).await?;
 
         Ok(HandlerResult::None(state))
     }
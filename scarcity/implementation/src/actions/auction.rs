@@ -0,0 +1,290 @@
+use async_trait::async_trait;
+
+use warp_erc1155::{
+    action::{self as Erc1155Action},
+    state::Balance,
+};
+
+use warp_scarcity::{
+    action::{
+        ActionResult, Bid, CancelAuction, CreateAuction, GetAuction, HandlerResult, ReadResponse,
+        SettleAuction, Transfer,
+    },
+    error::ContractError,
+    state::{Auction as AuctionState, AuctionBid, Parameters, AUCTION_EXTENSION},
+};
+
+use crate::{
+    actions::{
+        credits::credit,
+        listing::escrow_nft,
+        transfer::{quote_transfer, settle_transfer, transfer_as_contract, Settlement},
+        AsyncActionable,
+    },
+    contract_utils::{
+        foreign_call::ForeignContractCaller,
+        js_imports::{Block, Contract},
+    },
+    state::{Auction, State},
+    utils::{
+        check_payment_token, default_payment_token, is_op, is_super_op, scarcity_tiers, TokenId,
+    },
+};
+
+async fn find_auction(token_id: &str) -> Result<Auction, ContractError> {
+    Ok(State::auctions(token_id)
+        .ok_or(ContractError::AuctionNotFound(token_id.to_string()))
+        .await?
+        .get()
+        .await)
+}
+
+/// Lowest amount that can be bid on `auction`, bids being at least 1
+fn min_bid(auction: &Auction) -> Balance {
+    match &auction.highest_bid {
+        Some(bid) => Balance::new(
+            bid.amount
+                .value
+                .saturating_add(auction.min_increment.value.max(1)),
+        ),
+        None => Balance::new(auction.reserve_price.value.max(1)),
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for CreateAuction {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        if !matches!(
+            TokenId::parse(&self.token_id, &scarcity_tiers().await),
+            TokenId::Nft(_)
+        ) {
+            return Err(ContractError::TokenIsNotAnNFT(self.token_id));
+        }
+
+        if self.start_height >= self.end_height || self.end_height <= Block::height() as u32 {
+            return Err(ContractError::InvalidAuctionPeriod);
+        }
+
+        if State::auctions(&self.token_id).exists().await {
+            return Err(ContractError::TokenIsAuctioned(self.token_id));
+        }
+
+        if State::listings(&self.token_id).exists().await {
+            return Err(ContractError::TokenIsListed(self.token_id));
+        }
+
+        let payment_token = self
+            .payment_token
+            .or(default_payment_token(&self.token_id).await);
+        check_payment_token(&payment_token).await?;
+
+        let escrowed = escrow_nft(&caller, &self.token_id, foreign_caller).await?;
+
+        State::auctions(&self.token_id)
+            .set(&Auction {
+                token_id: self.token_id.clone(),
+                seller: caller,
+                reserve_price: self.reserve_price,
+                min_increment: self.min_increment,
+                payment_token,
+                start_height: self.start_height,
+                end_height: self.end_height,
+                escrowed,
+                highest_bid: None,
+            })
+            .await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Bid {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let mut auction = find_auction(&self.token_id).await?;
+        let height = Block::height() as u32;
+
+        if height < auction.start_height {
+            return Err(ContractError::AuctionNotStarted(self.token_id));
+        }
+
+        if height >= auction.end_height {
+            return Err(ContractError::AuctionEnded(self.token_id));
+        }
+
+        if caller == auction.seller {
+            return Err(ContractError::TransferFromAndToCannotBeEqual);
+        }
+
+        let min_bid = min_bid(&auction);
+        if self.amount.value < min_bid.value {
+            return Err(ContractError::BidTooLow(min_bid));
+        }
+
+        transfer_as_contract(
+            vec![Erc1155Action::Transfer {
+                from: Some(caller.clone()),
+                target: Contract::id(),
+                token_id: auction.payment_token.clone(),
+                qty: self.amount,
+            }],
+            foreign_caller,
+        )
+        .await?;
+
+        if let Some(outbid) = auction.highest_bid.take() {
+            credit(&outbid.bidder, &auction.payment_token, outbid.amount).await;
+        }
+
+        // Leave the other bidders some time to answer late bids
+        auction.end_height = auction.end_height.max(height + AUCTION_EXTENSION);
+        auction.highest_bid = Some(AuctionBid {
+            bidder: caller,
+            amount: self.amount,
+        });
+
+        State::auctions(&self.token_id).set(&auction).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for SettleAuction {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let auction = find_auction(&self.token_id).await?;
+
+        if (Block::height() as u32) < auction.end_height {
+            return Err(ContractError::AuctionNotEnded(self.token_id));
+        }
+
+        let contract = Contract::id();
+
+        if let Some(bid) = auction.highest_bid {
+            let transfer = Transfer {
+                from: auction.seller,
+                target: bid.bidder,
+                token_id: self.token_id.clone(),
+                price: bid.amount,
+                qty: None,
+                payment_token: auction.payment_token,
+            };
+            let quote = quote_transfer(&transfer).await?;
+            check_payment_token(&quote.payment_token).await?;
+
+            settle_transfer(
+                &transfer,
+                quote,
+                Settlement {
+                    payer: Some(contract.clone()),
+                    holder: auction.escrowed.then_some(contract),
+                    as_contract: true,
+                },
+                foreign_caller,
+            )
+            .await?;
+        } else if auction.escrowed {
+            transfer_as_contract(
+                vec![Erc1155Action::Transfer {
+                    from: Some(contract),
+                    target: auction.seller,
+                    token_id: Some(self.token_id.clone()),
+                    qty: Balance::new(1),
+                }],
+                foreign_caller,
+            )
+            .await?;
+        }
+
+        State::delete_auctions(&self.token_id).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for CancelAuction {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let auction = find_auction(&self.token_id).await?;
+        let is_op = is_op(&caller).await || is_super_op(&caller).await;
+
+        if caller != auction.seller && !is_op {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        if auction.highest_bid.is_some() && !is_op {
+            return Err(ContractError::AuctionHasBids(self.token_id));
+        }
+
+        if auction.escrowed {
+            transfer_as_contract(
+                vec![Erc1155Action::Transfer {
+                    from: Some(Contract::id()),
+                    target: auction.seller,
+                    token_id: Some(self.token_id.clone()),
+                    qty: Balance::new(1),
+                }],
+                foreign_caller,
+            )
+            .await?;
+        }
+
+        if let Some(bid) = auction.highest_bid {
+            credit(&bid.bidder, &auction.payment_token, bid.amount).await;
+        }
+
+        State::delete_auctions(&self.token_id).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetAuction {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let auction = State::auctions(&self.token_id)
+            .peek()
+            .await
+            .map(|auction| AuctionState {
+                token_id: auction.token_id,
+                seller: auction.seller,
+                reserve_price: auction.reserve_price,
+                min_increment: auction.min_increment,
+                payment_token: auction.payment_token,
+                start_height: auction.start_height,
+                end_height: auction.end_height,
+                escrowed: auction.escrowed,
+                highest_bid: auction.highest_bid,
+            });
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetAuction(auction),
+        ))
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     actions::AsyncActionable,
     contract_utils::foreign_call::ForeignContractCaller,
     state::State,
-    utils::{is_op, is_super_op},
+    utils::{is_op, is_super_op, scarcity_tiers, validate_scarcity_tiers},
 };
 
 #[async_trait(?Send)]
@@ -27,6 +27,7 @@ impl AsyncActionable for Configure {
         if !is_op
             || (self.super_operators.is_some() && !is_super_op)
             || (self.operators.is_some() && !is_super_op)
+            || (self.scarcity_tiers.is_some() && !is_super_op)
             || (self.can_evolve.is_some() && !is_super_op)
         {
             return Err(ContractError::UnauthorizedConfiguration);
@@ -55,6 +56,19 @@ impl AsyncActionable for Configure {
             State::settings().erc1155().set(&erc1155).await;
         }
 
+        if let Some(tiers) = self.scarcity_tiers {
+            validate_scarcity_tiers(&tiers, &scarcity_tiers().await)?;
+
+            State::settings().scarcity_tiers().set(&tiers).await;
+        }
+
+        if let Some(payment_tokens) = self.payment_tokens {
+            State::settings()
+                .payment_tokens()
+                .set(&payment_tokens)
+                .await;
+        }
+
         if let Some(_) = self.can_evolve {
             Ok(HandlerResult::Write(state))
         } else {
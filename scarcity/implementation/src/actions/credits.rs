@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+
+use warp_erc1155::{
+    action::{self as Erc1155Action},
+    state::Balance,
+};
+
+use warp_scarcity::{
+    action::{ActionResult, GetCredits, HandlerResult, ReadResponse, Withdraw},
+    error::ContractError,
+    state::{Credit, Parameters},
+};
+
+use crate::{
+    actions::{transfer::transfer_as_contract, AsyncActionable},
+    contract_utils::{foreign_call::ForeignContractCaller, js_imports::Contract},
+    state::State,
+};
+
+/// Adds `amount` to what `account` can withdraw in `payment_token`.
+///
+/// Crediting accounts instead of paying them right away prevents them from blocking the actions
+/// that owe them, e.g. by rejecting the tokens.
+pub async fn credit(account: &str, payment_token: &Option<String>, amount: Balance) {
+    let mut credits = State::credits(account).peek().await.unwrap_or_default();
+
+    match credits
+        .iter_mut()
+        .find(|credit| &credit.payment_token == payment_token)
+    {
+        Some(credit) => {
+            credit.amount = Balance::new(credit.amount.value.saturating_add(amount.value))
+        }
+        None => credits.push(Credit {
+            payment_token: payment_token.clone(),
+            amount,
+        }),
+    }
+
+    State::credits(account).set(&credits).await;
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Withdraw {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let credits = State::credits(&caller).peek().await.unwrap_or_default();
+
+        if credits.is_empty() {
+            return Err(ContractError::NothingToWithdraw);
+        }
+
+        let contract = Contract::id();
+        transfer_as_contract(
+            credits
+                .into_iter()
+                .map(|credit| Erc1155Action::Transfer {
+                    from: Some(contract.clone()),
+                    target: caller.clone(),
+                    token_id: credit.payment_token,
+                    qty: credit.amount,
+                })
+                .collect(),
+            foreign_caller,
+        )
+        .await?;
+
+        State::delete_credits(&caller).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetCredits {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let credits = State::credits(&self.account)
+            .peek()
+            .await
+            .unwrap_or_default();
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetCredits(credits),
+        ))
+    }
+}
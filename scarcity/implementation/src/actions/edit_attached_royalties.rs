@@ -42,7 +42,12 @@ impl AsyncActionable for EditAttachedRoyalties {
         };
 
         if let Some(new_royalties) = new_royalties {
-            attach_royalties_internal(&new_royalties).await?;
+            attach_royalties_internal(
+                &new_royalties,
+                old_royalties.minter,
+                old_royalties.payment_token,
+            )
+            .await?;
         } else {
             return Err(ContractError::RoyaltiesUnchanged);
         }
@@ -26,6 +26,8 @@ impl AsyncActionable for GetAllRoyalties {
                         base_id: royalties.base_id,
                         royalties: royalties.royalties,
                         rate: royalties.rate,
+                        minter: royalties.minter,
+                        payment_token: royalties.payment_token,
                     },
                 )
             })
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use warp_scarcity::{
+    action::{ActionResult, GetLazyDrop, HandlerResult, ReadResponse},
+    error::ContractError,
+    state::{LazyDrop, Parameters},
+};
+
+use crate::{
+    actions::AsyncActionable, contract_utils::foreign_call::ForeignContractCaller, state::State,
+};
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetLazyDrop {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let lazy_drop = State::lazy_drops(&self.base_id)
+            .ok_or(ContractError::LazyDropNotFound(self.base_id))
+            .await?
+            .get()
+            .await;
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetLazyDrop(LazyDrop {
+                base_id: lazy_drop.base_id,
+                scarcity: lazy_drop.scarcity,
+                editions: lazy_drop.editions,
+                minted: lazy_drop.minted,
+            }),
+        ))
+    }
+}
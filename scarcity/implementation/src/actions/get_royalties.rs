@@ -31,6 +31,8 @@ impl AsyncActionable for GetRoyalties {
                     base_id: attached_royalties.base_id,
                     royalties: attached_royalties.royalties,
                     rate: attached_royalties.rate,
+                    minter: attached_royalties.minter,
+                    payment_token: attached_royalties.payment_token,
                 },
             )),
         ))
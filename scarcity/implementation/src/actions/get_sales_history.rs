@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use warp_scarcity::{
+    action::{ActionResult, GetLastSale, GetSalesHistory, HandlerResult, ReadResponse},
+    error::ContractError,
+    state::Parameters,
+};
+
+use crate::{
+    actions::AsyncActionable, contract_utils::foreign_call::ForeignContractCaller, state::State,
+    utils::sale_key,
+};
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetSalesHistory {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(ContractError::InvalidLimit(limit));
+        }
+
+        let sales_count = State::sales_count(&self.token_id).peek().await.unwrap_or(0);
+
+        let start = self.after.map_or(0, |after| after.saturating_add(1));
+        let end = start.saturating_add(limit).min(sales_count);
+
+        let mut sales = Vec::new();
+        for index in start..end {
+            if let Some(sale) = State::sales(&sale_key(&self.token_id, index)).peek().await {
+                sales.push((index, sale));
+            }
+        }
+
+        let next = (end < sales_count).then(|| end - 1);
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetSalesHistory {
+                token_id: self.token_id,
+                sales,
+                sales_count,
+                next,
+            },
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetLastSale {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let last_sale = match State::sales_count(&self.token_id).peek().await {
+            Some(count) if count > 0 => {
+                State::sales(&sale_key(&self.token_id, count - 1))
+                    .peek()
+                    .await
+            }
+            _ => None,
+        };
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetLastSale(last_sale),
+        ))
+    }
+}
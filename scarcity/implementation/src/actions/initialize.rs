@@ -3,18 +3,82 @@ use std::collections::HashMap;
 use async_trait::async_trait;
 use warp_scarcity::{
     action::{ActionResult, HandlerResult, Initialize},
-    error::ContractError,
-    state::Parameters,
+    error::{ContractError, InitialStateViolation},
+    state::{InitialState, Parameters, UNIT},
 };
 
+use warp_common::validation::{find_duplicates, is_valid_key_segment, sorted};
+
 use crate::{
     actions::AsyncActionable,
     contract_utils::foreign_call::ForeignContractCaller,
     state::{AttachedRoyalties, Settings},
+    utils::{is_payment_token_allowed, is_valid_scarcity_tier},
 };
 
 use crate::state::State;
 
+/// Checks the attached royalties of `init_state`, ordered by base id, then its settings.
+fn validate_initial_state(init_state: &InitialState) -> Vec<InitialStateViolation> {
+    let mut violations = Vec::new();
+
+    for (id, ar) in sorted(&init_state.attached_royalties) {
+        if !is_valid_key_segment(id) {
+            violations.push(InitialStateViolation::InvalidBaseId(id.clone()));
+        }
+
+        if ar.base_id != *id {
+            violations.push(InitialStateViolation::BaseIdMismatch {
+                id: id.clone(),
+                base_id: ar.base_id.clone(),
+            });
+        }
+
+        if ar.rate > UNIT {
+            violations.push(InitialStateViolation::InvalidRate(id.clone()));
+        }
+
+        let royalties_sum = ar
+            .royalties
+            .values()
+            .try_fold(0u32, |sum, royalty| sum.checked_add(*royalty));
+
+        if royalties_sum != Some(UNIT) {
+            violations.push(InitialStateViolation::InvalidRoyalties(id.clone()));
+        }
+
+        if !is_payment_token_allowed(&ar.payment_token, &init_state.settings.payment_tokens) {
+            violations.push(InitialStateViolation::PaymentTokenNotAllowed(id.clone()));
+        }
+    }
+
+    let tiers = &init_state.settings.scarcity_tiers;
+
+    for (i, tier) in tiers.iter().enumerate() {
+        if !is_valid_scarcity_tier(tier, &tiers[..i]) {
+            violations.push(InitialStateViolation::InvalidScarcityTier(
+                tier.name.clone(),
+            ));
+        }
+    }
+
+    let settings = &init_state.settings;
+
+    for (field, entries) in [
+        ("superOperators", &settings.super_operators),
+        ("operators", &settings.operators),
+    ] {
+        for address in find_duplicates(entries) {
+            violations.push(InitialStateViolation::DuplicateEntry {
+                field: field.to_string(),
+                address: address.clone(),
+            });
+        }
+    }
+
+    violations
+}
+
 #[async_trait(?Send)]
 impl AsyncActionable for Initialize {
     async fn action(
@@ -24,6 +88,12 @@ impl AsyncActionable for Initialize {
         _foreign_caller: &mut ForeignContractCaller,
     ) -> ActionResult {
         if let Some(init_state) = parameters.initial_state {
+            let violations = validate_initial_state(&init_state);
+
+            if !violations.is_empty() {
+                return Err(ContractError::InvalidInitialState(violations));
+            }
+
             let init_state = State {
                 settings: Settings {
                     paused: init_state.settings.paused,
@@ -31,6 +101,8 @@ impl AsyncActionable for Initialize {
                     operators: init_state.settings.operators.clone(),
                     erc1155: init_state.settings.erc1155.clone(),
                     custodian: init_state.settings.custodian.clone(),
+                    scarcity_tiers: init_state.settings.scarcity_tiers.clone(),
+                    payment_tokens: init_state.settings.payment_tokens.clone(),
                 },
                 all_attached_royalties: HashMap::from_iter(
                     init_state.attached_royalties.iter().map(|(id, ar)| {
@@ -40,10 +112,23 @@ impl AsyncActionable for Initialize {
                                 base_id: ar.base_id.clone(),
                                 royalties: ar.royalties.clone(),
                                 rate: ar.rate,
+                                minter: ar.minter.clone(),
+                                payment_token: ar.payment_token.clone(),
                             },
                         )
                     }),
                 ),
+                first_sales_done: HashMap::new(),
+                lazy_drops: HashMap::new(),
+                sales_count: HashMap::new(),
+                sales: HashMap::new(),
+                listings: HashMap::new(),
+                seller_listings: HashMap::new(),
+                offers: HashMap::new(),
+                token_offers: HashMap::new(),
+                bidder_offers: HashMap::new(),
+                auctions: HashMap::new(),
+                credits: HashMap::new(),
             };
 
             State::init(&init_state).await;
@@ -0,0 +1,329 @@
+use async_trait::async_trait;
+
+use warp_erc1155::{
+    action::{self as Erc1155Action},
+    state::Balance,
+};
+
+use warp_scarcity::{
+    action::{
+        ActionResult, Buy, Delist, GetListing, GetSellerListings, HandlerResult, List,
+        ReadResponse, Transfer,
+    },
+    error::ContractError,
+    state::{Listing as ListingState, Parameters},
+};
+
+use crate::{
+    actions::{
+        transfer::{quote_transfer, settle_transfer, transfer_as_contract, Settlement},
+        AsyncActionable,
+    },
+    contract_utils::{
+        foreign_call::ForeignContractCaller,
+        js_imports::{Block, Contract},
+    },
+    state::{Listing, State},
+    utils::{
+        check_payment_token, default_payment_token, is_op, is_super_op, scarcity_tiers, TokenId,
+    },
+};
+
+fn is_expired(listing: &Listing) -> bool {
+    listing
+        .expires_at
+        .is_some_and(|expires_at| Block::height() as u32 >= expires_at)
+}
+
+fn to_listing_state(listing: Listing) -> ListingState {
+    ListingState {
+        token_id: listing.token_id,
+        seller: listing.seller,
+        price: listing.price,
+        payment_token: listing.payment_token,
+        expires_at: listing.expires_at,
+        escrowed: listing.escrowed,
+    }
+}
+
+async fn find_listing(token_id: &str) -> Result<Listing, ContractError> {
+    Ok(State::listings(token_id)
+        .ok_or(ContractError::ListingNotFound(token_id.to_string()))
+        .await?
+        .get()
+        .await)
+}
+
+/// Moves the NFT of `seller` to this contract until it's sold, which proves that `seller` owns it,
+/// and returns whether it was moved.
+///
+/// The editions of lazy drops that aren't minted yet are left as they are, only their minter being
+/// able to sell them.
+pub async fn escrow_nft(
+    seller: &str,
+    token_id: &str,
+    foreign_caller: &mut ForeignContractCaller,
+) -> Result<bool, ContractError> {
+    let contract = Contract::id();
+
+    // Checks that the NFT can be sold, e.g. that it has royalties attached
+    let quote = quote_transfer(&Transfer {
+        from: seller.to_string(),
+        target: contract.clone(),
+        token_id: token_id.to_string(),
+        price: Balance::new(0),
+        qty: None,
+        payment_token: None,
+    })
+    .await?;
+
+    if quote.token_movement.minted {
+        return Ok(false);
+    }
+
+    transfer_as_contract(
+        vec![Erc1155Action::Transfer {
+            from: Some(seller.to_string()),
+            target: contract,
+            token_id: Some(token_id.to_string()),
+            qty: Balance::new(1),
+        }],
+        foreign_caller,
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Deletes the listing of `token_id` along with its entry in the listings of its seller
+pub async fn remove_listing(token_id: &str) {
+    let listing = match State::listings(token_id).peek().await {
+        Some(listing) => listing,
+        None => return,
+    };
+
+    let token_ids = State::seller_listings(&listing.seller)
+        .peek()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|listed| listed != token_id)
+        .collect::<Vec<_>>();
+
+    if token_ids.is_empty() {
+        State::delete_seller_listings(&listing.seller).await;
+    } else {
+        State::seller_listings(&listing.seller)
+            .set(&token_ids)
+            .await;
+    }
+
+    State::delete_listings(token_id).await;
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for List {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        if !matches!(
+            TokenId::parse(&self.token_id, &scarcity_tiers().await),
+            TokenId::Nft(_)
+        ) {
+            return Err(ContractError::TokenIsNotAnNFT(self.token_id));
+        }
+
+        if let Some(expires_at) = self.expires_at {
+            if expires_at <= Block::height() as u32 {
+                return Err(ContractError::InvalidExpiration(expires_at));
+            }
+        }
+
+        let previous = State::listings(&self.token_id).peek().await;
+
+        // The NFTs of other sellers are either escrowed or editions of lazy drops that only their
+        // minter can sell
+        if previous
+            .as_ref()
+            .is_some_and(|previous| previous.seller != caller)
+        {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        if State::auctions(&self.token_id).exists().await {
+            return Err(ContractError::TokenIsAuctioned(self.token_id));
+        }
+
+        let payment_token = self
+            .payment_token
+            .or(default_payment_token(&self.token_id).await);
+        check_payment_token(&payment_token).await?;
+
+        let escrowed = if let Some(previous) = previous {
+            previous.escrowed
+        } else {
+            escrow_nft(&caller, &self.token_id, foreign_caller).await?
+        };
+
+        State::listings(&self.token_id)
+            .set(&Listing {
+                token_id: self.token_id.clone(),
+                seller: caller.clone(),
+                price: self.price,
+                payment_token,
+                expires_at: self.expires_at,
+                escrowed,
+            })
+            .await;
+
+        let mut token_ids = State::seller_listings(&caller)
+            .peek()
+            .await
+            .unwrap_or_default();
+
+        if !token_ids.contains(&self.token_id) {
+            token_ids.push(self.token_id);
+            State::seller_listings(&caller).set(&token_ids).await;
+        }
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Delist {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let listing = find_listing(&self.token_id).await?;
+
+        // Anyone may give expired NFTs back to their seller
+        if caller != listing.seller
+            && !is_expired(&listing)
+            && !is_op(&caller).await
+            && !is_super_op(&caller).await
+        {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        if listing.escrowed {
+            transfer_as_contract(
+                vec![Erc1155Action::Transfer {
+                    from: Some(Contract::id()),
+                    target: listing.seller,
+                    token_id: Some(self.token_id.clone()),
+                    qty: Balance::new(1),
+                }],
+                foreign_caller,
+            )
+            .await?;
+        }
+
+        remove_listing(&self.token_id).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Buy {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let listing = find_listing(&self.token_id).await?;
+
+        if is_expired(&listing) {
+            return Err(ContractError::ListingExpired(self.token_id));
+        }
+
+        if caller == listing.seller {
+            return Err(ContractError::TransferFromAndToCannotBeEqual);
+        }
+
+        let transfer = Transfer {
+            from: listing.seller,
+            target: caller,
+            token_id: self.token_id.clone(),
+            price: listing.price,
+            qty: None,
+            payment_token: listing.payment_token,
+        };
+        let quote = quote_transfer(&transfer).await?;
+        check_payment_token(&quote.payment_token).await?;
+
+        settle_transfer(
+            &transfer,
+            quote,
+            Settlement {
+                payer: None,
+                holder: listing.escrowed.then(Contract::id),
+                as_contract: true,
+            },
+            foreign_caller,
+        )
+        .await?;
+
+        remove_listing(&self.token_id).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetListing {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let listing = State::listings(&self.token_id)
+            .peek()
+            .await
+            .filter(|listing| !is_expired(listing))
+            .map(to_listing_state);
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetListing(listing),
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetSellerListings {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let token_ids = State::seller_listings(&self.seller)
+            .peek()
+            .await
+            .unwrap_or_default();
+
+        let mut listings = Vec::new();
+        for token_id in token_ids {
+            if let Some(listing) = State::listings(&token_id).peek().await {
+                if !is_expired(&listing) {
+                    listings.push(to_listing_state(listing));
+                }
+            }
+        }
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetSellerListings(listings),
+        ))
+    }
+}
@@ -15,38 +15,84 @@ use warp_scarcity::{
 use crate::{
     actions::{attach_royalties::attach_royalties_internal, AsyncActionable},
     contract_utils::{foreign_call::ForeignContractCaller, js_imports::Transaction},
-    state::State,
+    state::{LazyDrop, State},
+    utils::{check_payment_token, find_scarcity_tier, is_op, scarcity_tiers},
 };
 
 #[async_trait(?Send)]
 impl AsyncActionable for MintNft {
     async fn action(
         self,
-        _caller: String,
+        caller: String,
         state: Parameters,
         foreign_caller: &mut ForeignContractCaller,
     ) -> ActionResult {
+        // The editions of lazy drops are minted by this contract, so the ERC1155 contract doesn't
+        // check the minter role of the caller, whose roles can't be read from here
+        if self.lazy && !is_op(&caller).await {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
         let mut mints = Vec::new();
 
-        let (scarcity_name, editions_count): (String, u32) = (
-            self.scarcity.to_string(),
-            Into::<Option<u32>>::into(&self.scarcity).unwrap_or(1),
-        );
+        let tiers = scarcity_tiers().await;
+        let tier = find_scarcity_tier(&tiers, &self.scarcity.scarcity)
+            .ok_or_else(|| ContractError::UnknownScarcity(self.scarcity.scarcity.clone()))?;
+
+        // Tiers without maximum have no meaningful default amount
+        let editions_count = match (self.scarcity.amount, tier.max_editions) {
+            (Some(amount), _) => amount,
+            (None, Some(_)) => tier.editions,
+            (None, None) => return Err(ContractError::EditionsCountRequired(tier.name.clone())),
+        };
+
+        if editions_count == 0 || tier.max_editions.is_some_and(|max| editions_count > max) {
+            return Err(ContractError::InvalidEditionsCount(editions_count));
+        }
+
+        let base_id = self.base_id.clone().unwrap_or_else(Transaction::id);
 
-        attach_royalties_internal(&AttachRoyalties {
-            base_id: self.base_id.clone().unwrap_or_else(Transaction::id),
-            rate: self.rate,
-            royalties: self.royalties.clone(),
-        })
+        // Artists could otherwise replace the royalties of an existing NFT by minting editions of
+        // another scarcity under its base id
+        if State::all_attached_royalties(&base_id).exists().await {
+            return Err(ContractError::TokenAlreadyExists(base_id));
+        }
+
+        check_payment_token(&self.payment_token).await?;
+
+        attach_royalties_internal(
+            &AttachRoyalties {
+                base_id: base_id.clone(),
+                rate: self.rate,
+                royalties: self.royalties.clone(),
+            },
+            Some(caller),
+            self.payment_token.clone(),
+        )
         .await?;
 
+        if self.lazy {
+            State::lazy_drops(&base_id)
+                .set(&LazyDrop {
+                    base_id,
+                    scarcity: tier.name.clone(),
+                    editions: editions_count,
+                    minted: 0,
+                })
+                .await;
+
+            return Ok(HandlerResult::None(state));
+        }
+
         for edition in 0..editions_count {
-            let prefix = format!("{}-{}", edition + 1, scarcity_name);
+            let prefix = format!("{}-{}", edition + 1, tier.name);
 
             mints.push(Erc1155Action::Action::Mint(Erc1155Action::Mint {
                 base_id: self.base_id.clone(),
                 prefix: Some(prefix),
                 qty: Balance::new(1),
+                max_supply: Some(Balance::new(1)),
+                target: None,
             }));
         }
 
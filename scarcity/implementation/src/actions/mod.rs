@@ -3,15 +3,22 @@ use async_trait::async_trait;
 use warp_scarcity::{action::ActionResult, state::Parameters};
 
 pub mod attach_royalties;
+pub mod auction;
 pub mod batch;
 pub mod configure;
+pub mod credits;
 pub mod edit_attached_royalties;
 pub mod evolve;
 pub mod get_all_royalties;
+pub mod get_lazy_drop;
 pub mod get_royalties;
+pub mod get_sales_history;
 pub mod initialize;
+pub mod listing;
+pub mod offer;
 pub mod mint_nft;
 pub mod remove_attached_royalties;
+pub mod simulate;
 pub mod transfer;
 
 pub use attach_royalties::*;
@@ -0,0 +1,322 @@
+use async_trait::async_trait;
+
+use warp_erc1155::action::{self as Erc1155Action};
+
+use warp_scarcity::{
+    action::{
+        AcceptOffer, ActionResult, CancelOffer, GetBidderOffers, GetTokenOffers, HandlerResult,
+        MakeOffer, ReadResponse, Transfer,
+    },
+    error::ContractError,
+    state::{Offer as OfferState, Parameters},
+};
+
+use crate::{
+    actions::{
+        listing::remove_listing,
+        transfer::{quote_transfer, settle_transfer, transfer_as_contract, Settlement},
+        AsyncActionable,
+    },
+    contract_utils::{
+        foreign_call::ForeignContractCaller,
+        js_imports::{Block, Contract, Transaction},
+    },
+    state::{Offer, State},
+    utils::{
+        check_payment_token, default_payment_token, is_op, is_super_op, scarcity_tiers, TokenId,
+    },
+};
+
+fn is_expired(offer: &Offer) -> bool {
+    offer
+        .expires_at
+        .is_some_and(|expires_at| Block::height() as u32 >= expires_at)
+}
+
+fn to_offer_state(offer: Offer) -> OfferState {
+    OfferState {
+        id: offer.id,
+        token_id: offer.token_id,
+        bidder: offer.bidder,
+        price: offer.price,
+        payment_token: offer.payment_token,
+        expires_at: offer.expires_at,
+    }
+}
+
+/// Offers made in the same interaction through a batch share its transaction id, so the ones after
+/// the first get a numbered suffix.
+async fn new_offer_id() -> String {
+    let tx_id = Transaction::id();
+
+    let mut offer_id = tx_id.clone();
+    let mut suffix = 0;
+    while State::offers(&offer_id).exists().await {
+        suffix += 1;
+        offer_id = format!("{}-{}", tx_id, suffix);
+    }
+
+    offer_id
+}
+
+async fn find_offer(offer_id: &str) -> Result<Offer, ContractError> {
+    Ok(State::offers(offer_id)
+        .ok_or(ContractError::OfferNotFound(offer_id.to_string()))
+        .await?
+        .get()
+        .await)
+}
+
+async fn read_offers(offer_ids: Vec<String>) -> Vec<OfferState> {
+    let mut offers = Vec::new();
+    for offer_id in offer_ids {
+        if let Some(offer) = State::offers(&offer_id).peek().await {
+            offers.push(to_offer_state(offer));
+        }
+    }
+
+    offers
+}
+
+/// Deletes `offer` along with its entries in the offers of its token and of its bidder
+async fn remove_offer(offer: &Offer) {
+    let without_offer = |offer_ids: Option<Vec<String>>| {
+        offer_ids
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|offer_id| offer_id != &offer.id)
+            .collect::<Vec<_>>()
+    };
+
+    let token_offers = without_offer(State::token_offers(&offer.token_id).peek().await);
+    if token_offers.is_empty() {
+        State::delete_token_offers(&offer.token_id).await;
+    } else {
+        State::token_offers(&offer.token_id)
+            .set(&token_offers)
+            .await;
+    }
+
+    let bidder_offers = without_offer(State::bidder_offers(&offer.bidder).peek().await);
+    if bidder_offers.is_empty() {
+        State::delete_bidder_offers(&offer.bidder).await;
+    } else {
+        State::bidder_offers(&offer.bidder)
+            .set(&bidder_offers)
+            .await;
+    }
+
+    State::delete_offers(&offer.id).await;
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for MakeOffer {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        if self.price.value == 0 {
+            return Err(ContractError::TransferAmountMustBeHigherThanZero);
+        }
+
+        let base_id = match TokenId::parse(&self.token_id, &scarcity_tiers().await) {
+            TokenId::Nft(nft) => nft.base_id,
+            _ => return Err(ContractError::TokenIsNotAnNFT(self.token_id)),
+        };
+
+        if !State::all_attached_royalties(&base_id).exists().await {
+            return Err(ContractError::RoyaltiesNotFound(self.token_id));
+        }
+
+        if let Some(expires_at) = self.expires_at {
+            if expires_at <= Block::height() as u32 {
+                return Err(ContractError::InvalidExpiration(expires_at));
+            }
+        }
+
+        let payment_token = self
+            .payment_token
+            .or(default_payment_token(&self.token_id).await);
+        check_payment_token(&payment_token).await?;
+
+        transfer_as_contract(
+            vec![Erc1155Action::Transfer {
+                from: Some(caller.clone()),
+                target: Contract::id(),
+                token_id: payment_token.clone(),
+                qty: self.price,
+            }],
+            foreign_caller,
+        )
+        .await?;
+
+        let offer_id = new_offer_id().await;
+
+        State::offers(&offer_id)
+            .set(&Offer {
+                id: offer_id.clone(),
+                token_id: self.token_id.clone(),
+                bidder: caller.clone(),
+                price: self.price,
+                payment_token,
+                expires_at: self.expires_at,
+            })
+            .await;
+
+        let mut token_offers = State::token_offers(&self.token_id)
+            .peek()
+            .await
+            .unwrap_or_default();
+        token_offers.push(offer_id.clone());
+        State::token_offers(&self.token_id).set(&token_offers).await;
+
+        let mut bidder_offers = State::bidder_offers(&caller)
+            .peek()
+            .await
+            .unwrap_or_default();
+        bidder_offers.push(offer_id);
+        State::bidder_offers(&caller).set(&bidder_offers).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for CancelOffer {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let offer = find_offer(&self.offer_id).await?;
+
+        // Anyone may give the price of expired offers back to their bidder
+        if caller != offer.bidder
+            && !is_expired(&offer)
+            && !is_op(&caller).await
+            && !is_super_op(&caller).await
+        {
+            return Err(ContractError::UnauthorizedAddress(caller));
+        }
+
+        transfer_as_contract(
+            vec![Erc1155Action::Transfer {
+                from: Some(Contract::id()),
+                target: offer.bidder.clone(),
+                token_id: offer.payment_token.clone(),
+                qty: offer.price,
+            }],
+            foreign_caller,
+        )
+        .await?;
+
+        remove_offer(&offer).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for AcceptOffer {
+    async fn action(
+        self,
+        caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let offer = find_offer(&self.offer_id).await?;
+
+        if is_expired(&offer) {
+            return Err(ContractError::OfferExpired(self.offer_id));
+        }
+
+        // The highest bidder of the auction must get the NFT
+        if State::auctions(&offer.token_id).exists().await {
+            return Err(ContractError::TokenIsAuctioned(offer.token_id));
+        }
+
+        // A listed NFT is sold from the escrow, replacing its listing
+        let listing = State::listings(&offer.token_id)
+            .peek()
+            .await
+            .filter(|listing| listing.seller == caller);
+        let holder = listing
+            .as_ref()
+            .is_some_and(|listing| listing.escrowed)
+            .then(Contract::id);
+
+        let transfer = Transfer {
+            from: caller,
+            target: offer.bidder.clone(),
+            token_id: offer.token_id.clone(),
+            price: offer.price,
+            qty: None,
+            payment_token: offer.payment_token.clone(),
+        };
+        let quote = quote_transfer(&transfer).await?;
+        check_payment_token(&quote.payment_token).await?;
+
+        settle_transfer(
+            &transfer,
+            quote,
+            Settlement {
+                payer: Some(Contract::id()),
+                holder,
+                as_contract: true,
+            },
+            foreign_caller,
+        )
+        .await?;
+
+        if listing.is_some() {
+            remove_listing(&offer.token_id).await;
+        }
+
+        remove_offer(&offer).await;
+
+        Ok(HandlerResult::Write(state))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetTokenOffers {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let offer_ids = State::token_offers(&self.token_id)
+            .peek()
+            .await
+            .unwrap_or_default();
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetTokenOffers(read_offers(offer_ids).await),
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for GetBidderOffers {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let offer_ids = State::bidder_offers(&self.bidder)
+            .peek()
+            .await
+            .unwrap_or_default();
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::GetBidderOffers(read_offers(offer_ids).await),
+        ))
+    }
+}
@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+
+use warp_common::simulation::simulate;
+use warp_scarcity::{
+    action::{ActionResult, HandlerResult, ReadResponse, Simulate},
+    error::ContractError,
+    state::Parameters,
+};
+
+use crate::{
+    actions::AsyncActionable,
+    contract::handle,
+    contract_utils::{foreign_call::ForeignContractCaller, js_imports},
+};
+
+#[async_trait(?Send)]
+impl AsyncActionable for Simulate {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        if !foreign_caller.start_simulation() {
+            return Err(ContractError::ForbiddenNestedSimulation);
+        }
+
+        let mut simulation = simulate::<js_imports::Kv, _, _, _>(&state, async {
+            Ok(
+                match handle(state.clone(), *self.action, foreign_caller).await? {
+                    HandlerResult::Write(new_state) | HandlerResult::None(new_state) => {
+                        (new_state, None)
+                    }
+                    HandlerResult::Read(new_state, response) => (new_state, Some(response)),
+                },
+            )
+        })
+        .await;
+
+        let foreign_writes = foreign_caller.finish_simulation();
+        if simulation.error.is_none() {
+            simulation.foreign_writes = foreign_writes;
+        }
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::Simulate(Box::new(simulation)),
+        ))
+    }
+}
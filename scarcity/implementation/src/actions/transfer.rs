@@ -7,122 +7,339 @@ use warp_erc1155::{
 };
 
 use warp_scarcity::{
-    action::{ActionResult, HandlerResult, Transfer},
+    action::{
+        ActionResult, HandlerResult, Payout, PayoutRole, QuoteTransfer, ReadResponse,
+        TokenMovement, Transfer, TransferQuote,
+    },
     error::ContractError,
-    state::{Parameters, UNIT},
+    state::{Parameters, Sale, SaleKind, UNIT},
 };
 
 use crate::{
-    actions::AsyncActionable,
-    contract_utils::foreign_call::ForeignContractCaller,
+    actions::{listing::remove_listing, AsyncActionable},
+    contract_utils::{
+        foreign_call::ForeignContractCaller,
+        js_imports::{Block, Transaction},
+    },
+    royalties::split_price,
     state::State,
-    utils::{NftId, ShuffleId, TokenId},
+    utils::{check_payment_token, sale_key, scarcity_tiers, NftId, ShuffleId, TokenId},
 };
 
-#[async_trait(?Send)]
-impl AsyncActionable for Transfer {
-    async fn action(
-        self,
-        _caller: String,
-        state: Parameters,
-        foreign_caller: &mut ForeignContractCaller,
-    ) -> ActionResult {
-        let qty = self.qty.unwrap_or(Balance::new(1));
+/// Determines who `transfer` pays and the token it moves.
+pub async fn quote_transfer(transfer: &Transfer) -> Result<TransferQuote, ContractError> {
+    let qty = transfer.qty.unwrap_or(Balance::new(1));
 
-        if qty.value < 1 {
-            return Err(ContractError::TransferAmountMustBeHigherThanZero);
-        }
+    if qty.value < 1 {
+        return Err(ContractError::TransferAmountMustBeHigherThanZero);
+    }
 
-        let base_id = match TokenId::from(self.token_id.as_ref()) {
-            TokenId::Nft(NftId { base_id, .. }) => {
-                if qty.value != 1 {
-                    Err(ContractError::QtyMustBeOneForNftTransfers)?
-                } else {
-                    Ok(base_id)
-                }
+    let (base_id, nft) = match TokenId::parse(&transfer.token_id, &scarcity_tiers().await) {
+        TokenId::Nft(nft) => {
+            if qty.value != 1 {
+                Err(ContractError::QtyMustBeOneForNftTransfers)?
+            } else {
+                Ok((nft.base_id.clone(), Some(nft)))
             }
-            TokenId::Shuffle(ShuffleId { base_id }) => Ok(base_id),
-            TokenId::Token(token_id) => {
-                Err(ContractError::CantUseTransferWithSimpleTokens(token_id))
-            }
-        }?;
+        }
+        TokenId::Shuffle(ShuffleId { base_id }) => Ok((base_id, None)),
+        TokenId::Token(token_id) => Err(ContractError::CantUseTransferWithSimpleTokens(token_id)),
+    }?;
+
+    let attached_royalties = State::all_attached_royalties(&base_id)
+        .ok_or(ContractError::RoyaltiesNotFound(transfer.token_id.clone()))
+        .await?
+        .get()
+        .await;
+
+    // NFTs whose minter is unknown were all minted by the custodian, whose sales are first sales
+    let is_resell = if attached_royalties.minter.is_some() {
+        State::first_sales_done(&transfer.token_id).exists().await
+    } else {
+        transfer.from != State::settings().custodian().get().await
+    };
+
+    // The editions of lazy drops only exist in the ERC1155 contract once they are first sold
+    let minted = !is_resell && State::lazy_drops(&base_id).exists().await;
 
-        let attached_royalties = State::all_attached_royalties(&base_id)
-            .ok_or(ContractError::RoyaltiesNotFound(self.token_id.clone()))
+    if minted {
+        let lazy_drop = State::lazy_drops(&base_id)
+            .ok_or(ContractError::LazyDropNotFound(base_id.clone()))
             .await?
             .get()
             .await;
 
-        let token_owner = self.from.clone();
-
-        let is_resell = self.from != State::settings().custodian().get().await;
-
-        let rate = if is_resell {
-            attached_royalties.rate
-        } else {
-            UNIT
-        };
-
-        let mut transfers: Vec<Erc1155Action::Transfer> = Vec::new();
-
-        if self.price.value > 0 {
-            // If this transfer is a resell, pay the NFT owner.
-            if is_resell {
-                transfers.push(Erc1155Action::Transfer {
-                    from: Some(self.target.clone()),
-                    target: token_owner.clone(),
-                    token_id: None,
-                    qty: Balance::new(
-                        (self.price.value as f32 * ((UNIT - rate) as f32 / UNIT as f32))
-                            as BalancePrecision,
-                    ),
-                });
+        // Only the canonical id of each edition may be minted
+        let is_edition = nft.is_some_and(|nft| {
+            nft.to_string() == transfer.token_id
+                && nft.scarcity == lazy_drop.scarcity
+                && (1..=lazy_drop.editions).contains(&nft.edition)
+        });
+
+        if !is_edition {
+            return Err(ContractError::TokenDoesNotExist(transfer.token_id.clone()));
+        }
+
+        if attached_royalties.minter.as_ref() != Some(&transfer.from) {
+            return Err(ContractError::UnauthorizedTransfer(transfer.from.clone()));
+        }
+    }
+
+    let rate = if is_resell {
+        attached_royalties.rate
+    } else {
+        UNIT
+    };
+
+    let mut payouts = Vec::new();
+
+    if transfer.price.value > 0 {
+        let split = split_price(transfer.price.value, rate, &attached_royalties.royalties)?;
+
+        // Pay the NFT owner, who is also given the rounding dust of the royalties when this
+        // transfer isn't a resell.
+        let seller_payout = (transfer.from.clone(), split.seller, PayoutRole::Seller);
+        let shareholder_payouts = split
+            .shareholders
+            .into_iter()
+            .map(|(address, amount)| (address, amount, PayoutRole::Shareholder));
+
+        for (address, amount, role) in std::iter::once(seller_payout).chain(shareholder_payouts) {
+            // Skip empty payouts and the ones the buyer would make to themselves
+            if transfer.target == address || amount == 0 {
+                continue;
             }
 
-            // Pay the share holders.
-            attached_royalties
-                .royalties
-                .iter()
-                .for_each(|(address, royalty_share)| {
-                    // Skip paying the shareholder if they are the buyer themselves
-                    if self.target == *address {
-                        return;
-                    }
-
-                    let royalty_amount = (self.price.value as f32
-                        * (*royalty_share as f32 / (UNIT as f32 * (UNIT as f32 / rate as f32))))
-                        as BalancePrecision;
-
-                    transfers.push(Erc1155Action::Transfer {
-                        from: Some(self.target.clone()),
-                        target: address.clone(),
-                        token_id: None,
-                        qty: Balance::new(royalty_amount),
-                    });
-                });
+            payouts.push(Payout {
+                address,
+                amount: Balance::new(amount),
+                role,
+            });
         }
+    }
 
-        // Transfer the token.
-        transfers.push(Erc1155Action::Transfer {
-            from: Some(token_owner),
-            target: self.target.clone(),
-            token_id: Some(self.token_id),
+    Ok(TransferQuote {
+        is_resell,
+        payment_token: transfer
+            .payment_token
+            .clone()
+            .or(attached_royalties.payment_token),
+        payouts,
+        token_movement: TokenMovement {
+            from: transfer.from.clone(),
+            target: transfer.target.clone(),
+            token_id: transfer.token_id.clone(),
             qty,
-        });
+            minted,
+        },
+    })
+}
 
-        let transfers = transfers
-            .into_iter()
-            .map(Erc1155Action::Action::Transfer)
-            .collect();
+/// Appends `sale` to the provenance of `token_id`.
+async fn record_sale(token_id: &str, sale: Sale) {
+    let index = State::sales_count(token_id).peek().await.unwrap_or(0);
+
+    State::sales(&sale_key(token_id, index)).set(&sale).await;
+    State::sales_count(token_id).set(&(index + 1)).await;
+}
+
+/// Makes `transfers` in the ERC1155 contract as this contract, which their senders must have
+/// approved unless it's this contract.
+pub async fn transfer_as_contract(
+    transfers: Vec<Erc1155Action::Transfer>,
+    foreign_caller: &mut ForeignContractCaller,
+) -> Result<(), ContractError> {
+    let actions = transfers
+        .into_iter()
+        .map(Erc1155Action::Action::Transfer)
+        .collect();
+
+    foreign_caller
+        .write::<Erc1155ContractError, Erc1155Action::Action>(
+            &State::settings().erc1155().get().await,
+            Erc1155Action::Action::AsDirectCaller(Erc1155Action::AsDirectCaller {
+                action: Box::new(Erc1155Action::Action::Batch(Erc1155Action::Batch {
+                    actions,
+                })),
+            }),
+        )
+        .await
+        .map_err(ContractError::Erc1155Error)?;
+
+    Ok(())
+}
+
+/// How `settle_transfer` executes a sale
+#[derive(Default)]
+pub struct Settlement {
+    /// Account paying the price instead of the buyer, e.g. this contract for escrowed funds. The
+    /// part of the price that isn't paid out, which the buyer would pay to themselves, goes back
+    /// to the buyer.
+    pub payer: Option<String>,
+    /// Account holding the token instead of the seller, e.g. this contract for escrowed NFTs
+    pub holder: Option<String>,
+    /// Whether this contract makes the ERC1155 actions instead of the transaction's signer, in
+    /// which case the payer and the holder must have approved it
+    pub as_contract: bool,
+}
 
-        foreign_caller
-            .write::<Erc1155ContractError, Erc1155Action::Action>(
-                &State::settings().erc1155().get().await,
-                Erc1155Action::Action::Batch(Erc1155Action::Batch { actions: transfers }),
-            )
-            .await
-            .map_err(ContractError::Erc1155Error)?;
+/// Pays the seller and the shareholders of `transfer` and moves its token as its `quote` describes,
+/// then records the sale.
+pub async fn settle_transfer(
+    transfer: &Transfer,
+    quote: TransferQuote,
+    settlement: Settlement,
+    foreign_caller: &mut ForeignContractCaller,
+) -> Result<(), ContractError> {
+    let payer = settlement.payer.unwrap_or_else(|| transfer.target.clone());
+
+    let paid_out = quote
+        .payouts
+        .iter()
+        .map(|payout| payout.amount.value)
+        .sum::<BalancePrecision>();
+    let refund = transfer.price.value - paid_out;
+
+    let mut actions: Vec<Erc1155Action::Action> = quote
+        .payouts
+        .into_iter()
+        .map(|payout| Erc1155Action::Transfer {
+            from: Some(payer.clone()),
+            target: payout.address,
+            token_id: quote.payment_token.clone(),
+            qty: payout.amount,
+        })
+        .chain(
+            (payer != transfer.target && refund > 0).then(|| Erc1155Action::Transfer {
+                from: Some(payer.clone()),
+                target: transfer.target.clone(),
+                token_id: quote.payment_token.clone(),
+                qty: Balance::new(refund),
+            }),
+        )
+        .map(Erc1155Action::Action::Transfer)
+        .collect();
+
+    let movement = quote.token_movement;
+    let mut minted_drop = None;
+
+    if movement.minted {
+        // Mint the edition of the lazy drop directly to the buyer.
+        let nft = NftId::parse(&movement.token_id, &scarcity_tiers().await)
+            .map_err(|_| ContractError::TokenDoesNotExist(movement.token_id.clone()))?;
+
+        actions.push(Erc1155Action::Action::Mint(Erc1155Action::Mint {
+            base_id: Some(nft.base_id.clone()),
+            prefix: Some(format!("{}-{}", nft.edition, nft.scarcity)),
+            qty: movement.qty,
+            max_supply: Some(Balance::new(1)),
+            target: Some(movement.target),
+        }));
+
+        minted_drop = Some(nft.base_id);
+    } else {
+        // Transfer the token.
+        actions.push(Erc1155Action::Action::Transfer(Erc1155Action::Transfer {
+            from: Some(settlement.holder.unwrap_or(movement.from)),
+            target: movement.target,
+            token_id: Some(movement.token_id),
+            qty: movement.qty,
+        }));
+    }
+
+    let batch = Erc1155Action::Action::Batch(Erc1155Action::Batch { actions });
+    let batch = if settlement.as_contract {
+        Erc1155Action::Action::AsDirectCaller(Erc1155Action::AsDirectCaller {
+            action: Box::new(batch),
+        })
+    } else {
+        batch
+    };
+
+    foreign_caller
+        .write::<Erc1155ContractError, Erc1155Action::Action>(
+            &State::settings().erc1155().get().await,
+            batch,
+        )
+        .await
+        .map_err(ContractError::Erc1155Error)?;
+
+    if !quote.is_resell {
+        State::first_sales_done(&transfer.token_id).set(&true).await;
+    }
+
+    // Listings of NFTs that this contract doesn't hold are outdated once they're sold
+    if State::listings(&transfer.token_id)
+        .peek()
+        .await
+        .is_some_and(|listing| !listing.escrowed)
+    {
+        remove_listing(&transfer.token_id).await;
+    }
+
+    record_sale(
+        &transfer.token_id,
+        Sale {
+            from: transfer.from.clone(),
+            to: transfer.target.clone(),
+            qty: movement.qty,
+            price: transfer.price,
+            payment_token: quote.payment_token,
+            block_height: Block::height() as u32,
+            tx_id: Transaction::id(),
+            kind: if quote.is_resell {
+                SaleKind::Secondary
+            } else {
+                SaleKind::Primary
+            },
+        },
+    )
+    .await;
+
+    if let Some(base_id) = minted_drop {
+        let lazy_drop = State::lazy_drops(&base_id)
+            .ok_or(ContractError::LazyDropNotFound(base_id.clone()))
+            .await?;
+        let mut drop = lazy_drop.get().await;
+
+        drop.minted += 1;
+        lazy_drop.set(&drop).await;
+    }
+
+    Ok(())
+}
+
+#[async_trait(?Send)]
+impl AsyncActionable for Transfer {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let quote = quote_transfer(&self).await?;
+        check_payment_token(&quote.payment_token).await?;
+
+        settle_transfer(&self, quote, Settlement::default(), foreign_caller).await?;
 
         Ok(HandlerResult::Write(state))
     }
 }
+
+#[async_trait(?Send)]
+impl AsyncActionable for QuoteTransfer {
+    async fn action(
+        self,
+        _caller: String,
+        state: Parameters,
+        _foreign_caller: &mut ForeignContractCaller,
+    ) -> ActionResult {
+        let quote = quote_transfer(&self.into()).await?;
+        check_payment_token(&quote.payment_token).await?;
+
+        Ok(HandlerResult::Read(
+            state,
+            ReadResponse::QuoteTransfer(quote),
+        ))
+    }
+}
@@ -14,7 +14,41 @@ use crate::{
 };
 
 pub fn is_action_read(action: &Action) -> bool {
-    matches!(action, Action::GetRoyalties(_) | Action::GetAllRoyalties(_))
+    matches!(
+        action,
+        Action::GetRoyalties(_)
+            | Action::GetAllRoyalties(_)
+            | Action::GetLazyDrop(_)
+            | Action::GetSalesHistory(_)
+            | Action::GetLastSale(_)
+            | Action::GetListing(_)
+            | Action::GetSellerListings(_)
+            | Action::GetTokenOffers(_)
+            | Action::GetBidderOffers(_)
+            | Action::GetAuction(_)
+            | Action::GetCredits(_)
+            | Action::QuoteTransfer(_)
+            | Action::Simulate(_)
+    )
+}
+
+/// Write actions that the users may make for themselves
+pub fn is_user_action(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::MintNft(_)
+            | Action::List(_)
+            | Action::Delist(_)
+            | Action::Buy(_)
+            | Action::MakeOffer(_)
+            | Action::CancelOffer(_)
+            | Action::AcceptOffer(_)
+            | Action::CreateAuction(_)
+            | Action::Bid(_)
+            | Action::SettleAuction(_)
+            | Action::CancelAuction(_)
+            | Action::Withdraw(_)
+    )
 }
 
 pub fn allowed_in_pause(action: &Action) -> bool {
@@ -46,8 +80,11 @@ pub async fn handle(
         return Err(ContractError::ContractIsPaused);
     }
 
-    // NOTE: Currently, only Pianity is allowed to transfer NFTs
+    // NOTE: Only Pianity is allowed to transfer NFTs on behalf of their owners, who may sell them
+    // through listings instead. Anyone may mint NFTs, the ERC1155 contract requiring the minter
+    // role from the caller, but only operators may drop them lazily.
     if !is_action_read(&action)
+        && !is_user_action(&action)
         && !is_op(&direct_caller).await
         && !is_super_op(&direct_caller).await
     {
@@ -60,6 +97,11 @@ pub async fn handle(
         Action::GetAllRoyalties(action) => {
             action.action(direct_caller, state, foreign_caller).await
         }
+        Action::GetLazyDrop(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetSalesHistory(action) => {
+            action.action(direct_caller, state, foreign_caller).await
+        }
+        Action::GetLastSale(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::AttachRoyalties(action) => {
             action.action(direct_caller, state, foreign_caller).await
         }
@@ -70,9 +112,33 @@ pub async fn handle(
             action.action(direct_caller, state, foreign_caller).await
         }
         Action::Transfer(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::QuoteTransfer(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::List(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::Delist(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::Buy(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetListing(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetSellerListings(action) => {
+            action.action(direct_caller, state, foreign_caller).await
+        }
+        Action::MakeOffer(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::CancelOffer(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::AcceptOffer(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetTokenOffers(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetBidderOffers(action) => {
+            action.action(direct_caller, state, foreign_caller).await
+        }
+        Action::CreateAuction(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::Bid(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::SettleAuction(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::CancelAuction(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetAuction(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::Withdraw(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::GetCredits(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::Configure(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::Evolve(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::Simulate(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::Batch(action) => action.action(direct_caller, state, foreign_caller).await,
+        Action::BatchV2(action) => action.action(direct_caller, state, foreign_caller).await,
         Action::MintNft(action) => action.action(direct_caller, state, foreign_caller).await,
     }
 }
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use serde::Serialize;
 use serde::{de::DeserializeOwned, Deserialize};
 
+use warp_erc1155::action::ForeignWrite;
 use warp_scarcity::error::{ForeignReadError, ForeignWriteError};
 
 use crate::contract_utils::js_imports::SmartWeave;
@@ -39,6 +40,10 @@ struct InternalWriteResult {
 
 pub struct ForeignContractCaller {
     states: HashMap<String, ForeignContractState>,
+    /// Writes that weren't made because they happened during a simulation, `None` outside of one
+    simulated_writes: Option<Vec<ForeignWrite>>,
+    /// Number of writes made so far
+    writes: u32,
 }
 
 impl Default for ForeignContractCaller {
@@ -51,9 +56,31 @@ impl ForeignContractCaller {
     pub fn new() -> Self {
         Self {
             states: HashMap::new(),
+            simulated_writes: None,
+            writes: 0,
         }
     }
 
+    pub fn writes(&self) -> u32 {
+        self.writes
+    }
+
+    /// Starts recording writes instead of making them, returns `false` if a simulation is already
+    /// running
+    pub fn start_simulation(&mut self) -> bool {
+        if self.simulated_writes.is_some() {
+            return false;
+        }
+
+        self.simulated_writes = Some(Vec::new());
+        true
+    }
+
+    /// Stops recording writes and returns the ones that were recorded
+    pub fn finish_simulation(&mut self) -> Vec<ForeignWrite> {
+        self.simulated_writes.take().unwrap_or_default()
+    }
+
     pub async fn read(
         &mut self,
         contract_address: &String,
@@ -75,6 +102,20 @@ impl ForeignContractCaller {
         contract_address: &String,
         input: INPUT,
     ) -> Result<&ForeignContractState, ForeignWriteError<ERROR>> {
+        // During a simulation the write is only recorded, and assumed to leave the state as is
+        if let Some(simulated_writes) = self.simulated_writes.as_mut() {
+            simulated_writes.push(ForeignWrite {
+                contract_id: contract_address.to_string(),
+                input: serde_json::to_value(&input).unwrap(),
+            });
+            self.writes += 1;
+
+            return self
+                .read(contract_address)
+                .await
+                .map_err(|_err| ForeignWriteError::ParseError);
+        }
+
         // let input = JsValue::jfrom_serde(&input).unwrap();
         let input = serde_wasm_bindgen::to_value(&input).unwrap();
 
@@ -86,6 +127,7 @@ impl ForeignContractCaller {
 
         match result {
             ForeignCallResult::Ok(state) => {
+                self.writes += 1;
                 self.states
                     .insert(contract_address.to_string(), state.state);
                 Ok(self.states.get(contract_address).unwrap())
@@ -1,5 +1,6 @@
 mod actions;
 mod contract;
 pub mod contract_utils;
+mod royalties;
 mod state;
 mod utils;
@@ -0,0 +1,165 @@
+use warp_erc1155::state::BalancePrecision;
+use warp_scarcity::{
+    error::ContractError,
+    state::{Royalties, UNIT},
+};
+
+/// How the price of a sale is split between the seller and the shareholders of the token
+#[derive(Debug, PartialEq, Eq)]
+pub struct Payouts {
+    /// Amount paid to the seller, including the rounding dust of the shareholders' amounts
+    pub seller: BalancePrecision,
+    /// Amount paid to each shareholder, sorted by address
+    pub shareholders: Vec<(String, BalancePrecision)>,
+}
+
+/// Computes `amount * fraction / UNIT` rounded down, without overflowing as long as
+/// `fraction <= UNIT`.
+fn unit_fraction(amount: BalancePrecision, fraction: u32) -> BalancePrecision {
+    let unit = UNIT as BalancePrecision;
+    let fraction = fraction as BalancePrecision;
+
+    amount / unit * fraction + amount % unit * fraction / unit
+}
+
+/// Splits `price` between the seller and the shareholders: `rate` of it is shared according to
+/// `royalties` and the seller gets the rest.
+///
+/// Every amount is rounded down and the remainder goes to the seller, so that the payouts always
+/// add up to `price`.
+pub fn split_price(
+    price: BalancePrecision,
+    rate: u32,
+    royalties: &Royalties,
+) -> Result<Payouts, ContractError> {
+    if rate > UNIT {
+        return Err(ContractError::InvalidRate);
+    }
+
+    let royalties_amount = unit_fraction(price, rate);
+
+    let mut shareholders = royalties
+        .iter()
+        .map(|(address, share)| {
+            if *share > UNIT {
+                Err(ContractError::InvalidRoyalties)
+            } else {
+                Ok((address.clone(), unit_fraction(royalties_amount, *share)))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    shareholders.sort();
+
+    let seller = shareholders
+        .iter()
+        .try_fold(price, |rest, (_, amount)| rest.checked_sub(*amount))
+        .ok_or(ContractError::InvalidRoyalties)?;
+
+    Ok(Payouts {
+        seller,
+        shareholders,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn total(payouts: &Payouts) -> BalancePrecision {
+        payouts.seller
+            + payouts
+                .shareholders
+                .iter()
+                .map(|(_, amount)| amount)
+                .sum::<BalancePrecision>()
+    }
+
+    /// Royalty maps whose shares add up to `UNIT`, like the ones attached to tokens
+    fn valid_royalties() -> impl Strategy<Value = Royalties> {
+        prop::collection::vec(0..=UNIT, 0..10).prop_map(|mut cuts| {
+            cuts.push(0);
+            cuts.push(UNIT);
+            cuts.sort();
+
+            cuts.windows(2)
+                .enumerate()
+                .map(|(i, cut)| (format!("shareholder-{}", i), cut[1] - cut[0]))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn gives_the_dust_to_the_seller() {
+        let royalties = HashMap::from([
+            ("a".to_string(), 333_333),
+            ("b".to_string(), 333_333),
+            ("c".to_string(), 333_334),
+        ]);
+
+        assert_eq!(
+            split_price(10, UNIT, &royalties).unwrap(),
+            Payouts {
+                seller: 1,
+                shareholders: vec![
+                    ("a".to_string(), 3),
+                    ("b".to_string(), 3),
+                    ("c".to_string(), 3),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn is_exact_beyond_f32_precision() {
+        let royalties = HashMap::from([("a".to_string(), UNIT)]);
+
+        assert_eq!(
+            split_price(16_777_217, UNIT / 10, &royalties).unwrap(),
+            Payouts {
+                seller: 15_099_496,
+                shareholders: vec![("a".to_string(), 1_677_721)],
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_rates_above_unit() {
+        assert!(matches!(
+            split_price(100, UNIT + 1, &Royalties::new()),
+            Err(ContractError::InvalidRate)
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn valid_royalties_add_up_to_price(
+            price in any::<BalancePrecision>(),
+            rate in 0..=UNIT,
+            royalties in valid_royalties(),
+        ) {
+            let payouts = split_price(price, rate, &royalties).unwrap();
+
+            prop_assert_eq!(total(&payouts), price);
+            prop_assert!(price - payouts.seller <= unit_fraction(price, rate));
+        }
+
+        #[test]
+        fn any_royalties_add_up_to_price_or_fail(
+            price in any::<BalancePrecision>(),
+            rate in 0..=UNIT,
+            royalties in prop::collection::hash_map("[a-z]{1,8}", 0..=2 * UNIT, 0..10),
+        ) {
+            match split_price(price, rate, &royalties) {
+                Ok(payouts) => prop_assert_eq!(total(&payouts), price),
+                Err(error) => {
+                    prop_assert!(matches!(error, ContractError::InvalidRoyalties));
+                    prop_assert!(royalties.values().map(|share| *share as u64).sum::<u64>() > UNIT as u64);
+                }
+            }
+        }
+    }
+}
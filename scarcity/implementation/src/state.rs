@@ -2,9 +2,14 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use kv_storage::{kv, KvStorage};
+use kv_storage::{kv, overlay::Overlay, KvStorage};
+use warp_erc1155::state::Balance;
+use warp_scarcity::state::{AuctionBid, Credit, Sale, ScarcityTier};
 
-use crate::contract_utils::js_imports::Kv;
+use crate::contract_utils::js_imports;
+
+/// Writes are kept in memory instead of being persisted while an action is simulated
+pub type Kv = Overlay<js_imports::Kv>;
 
 /**
  * address -> share
@@ -17,19 +22,61 @@ pub struct AttachedRoyalties {
     pub base_id: String,
     pub royalties: Royalties,
     pub rate: u32,
-    // NOTE: The following will only be necessary when artists will be allowed to mint instead of
-    // Pianity. At the moment Pianity is acting as the custodian so we can just check for the
-    // `custodian` field in the contract's settings.
-    //
-    // /// Address of the original owner of the NFT
-    // ///
-    // /// NOTE: This is required because in order to determine whether a transfer is a sell or a
-    // /// resell, we have to know who originally owned the NFT.
-    // ///
-    // /// In theory this should be obtained by simply checking what's the address that minted the NFT
-    // /// however it isn't possible without using the unsafe client so we resort to manually storing
-    // /// the address.
-    // pub minter: String,
+    /// Address that minted the NFT and originally owned it, unknown for the NFTs minted before
+    /// artists were allowed to mint and for royalties attached to existing tokens
+    pub minter: Option<String>,
+    /// Token that the NFTs are sold for unless a sale specifies one
+    pub payment_token: Option<String>,
+}
+
+/// NFT whose editions are minted in the ERC1155 contract when they are first sold
+#[derive(Debug)]
+#[kv(impl = "Kv")]
+pub struct LazyDrop {
+    pub base_id: String,
+    /// Name of the scarcity tier
+    pub scarcity: String,
+    pub editions: u32,
+    /// Editions minted so far
+    pub minted: u32,
+}
+
+#[derive(Debug)]
+#[kv(impl = "Kv")]
+pub struct Listing {
+    pub token_id: String,
+    pub seller: String,
+    pub price: Balance,
+    pub payment_token: Option<String>,
+    pub expires_at: Option<u32>,
+    /// Whether this contract holds the NFT until it's bought or delisted
+    pub escrowed: bool,
+}
+
+#[derive(Debug)]
+#[kv(impl = "Kv")]
+pub struct Offer {
+    pub id: String,
+    pub token_id: String,
+    pub bidder: String,
+    pub price: Balance,
+    pub payment_token: Option<String>,
+    pub expires_at: Option<u32>,
+}
+
+#[derive(Debug)]
+#[kv(impl = "Kv")]
+pub struct Auction {
+    pub token_id: String,
+    pub seller: String,
+    pub reserve_price: Balance,
+    pub min_increment: Balance,
+    pub payment_token: Option<String>,
+    pub start_height: u32,
+    pub end_height: u32,
+    /// Whether this contract holds the NFT until the auction is settled
+    pub escrowed: bool,
+    pub highest_bid: Option<AuctionBid>,
 }
 
 #[kv(impl = "Kv", subpath)]
@@ -42,13 +89,17 @@ pub struct Settings {
     /// Address of the attached ERC1155-compliant contract
     pub erc1155: String,
 
-    /// NOTE: Currently only Pianity is allowed to do mints and transfers which means that
-    /// ownership always defaults to Pianity. This field represents the address to which ownership
-    /// always defaults in the ERC1155 contract.
+    /// Address to which the ownership of the NFTs minted by Pianity defaults in the ERC1155
+    /// contract.
     ///
-    /// It is required in order to, for example, determine whether a transfer represents a sell or
-    /// a resell.
+    /// NOTE: It is only used to determine whether a transfer represents a sell or a resell for the
+    /// NFTs whose minter is unknown, the first sale of the others being tracked.
     pub custodian: String,
+
+    pub scarcity_tiers: Vec<ScarcityTier>,
+
+    /// Tokens of the ERC1155 contract that NFTs may be sold for besides its default token
+    pub payment_tokens: Vec<String>,
 }
 
 // #[derive(JsonSchema, Serialize, Deserialize, Clone, Default, Debug)]
@@ -58,4 +109,36 @@ pub struct State {
     settings: Settings,
     #[kv(map)]
     all_attached_royalties: AttachedRoyalties,
+    /// Token ids of the NFTs whose first sale is done
+    #[kv(map)]
+    first_sales_done: bool,
+    #[kv(map)]
+    lazy_drops: LazyDrop,
+    /// token id -> number of sales recorded
+    #[kv(map)]
+    sales_count: u32,
+    /// `<token id>.<index>` -> sale, indexed from the oldest, see `sale_key`
+    #[kv(map)]
+    sales: Sale,
+    /// token id -> listing
+    #[kv(map)]
+    listings: Listing,
+    /// seller -> token ids of their listings
+    #[kv(map)]
+    seller_listings: Vec<String>,
+    /// offer id -> offer
+    #[kv(map)]
+    offers: Offer,
+    /// token id -> ids of the offers made for it
+    #[kv(map)]
+    token_offers: Vec<String>,
+    /// bidder -> ids of their offers
+    #[kv(map)]
+    bidder_offers: Vec<String>,
+    /// token id -> auction
+    #[kv(map)]
+    auctions: Auction,
+    /// account -> amounts it can withdraw, one per payment token
+    #[kv(map)]
+    credits: Vec<Credit>,
 }
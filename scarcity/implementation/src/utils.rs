@@ -1,4 +1,7 @@
-use warp_scarcity::action::Scarcity;
+use warp_scarcity::{
+    error::ContractError,
+    state::{default_scarcity_tiers, ScarcityTier},
+};
 
 use crate::state::State;
 
@@ -18,22 +21,114 @@ pub async fn is_super_op(address: &str) -> bool {
         .contains(&address.into())
 }
 
-// TODO: This code is mostly duplicated from the Shuffle contract. It should be refactored to be
-// shared instead.
+/// Contracts initialized before tiers were configurable don't have them stored, in which case the
+/// default ones apply.
+pub async fn scarcity_tiers() -> Vec<ScarcityTier> {
+    State::settings()
+        .scarcity_tiers()
+        .peek()
+        .await
+        .unwrap_or_else(default_scarcity_tiers)
+}
+
+/// Contracts initialized before payment tokens were configurable only allow the default token
+pub async fn payment_tokens() -> Vec<String> {
+    State::settings()
+        .payment_tokens()
+        .peek()
+        .await
+        .unwrap_or_default()
+}
+
+/// The ERC1155 contract's default token, represented by `None`, is always allowed
+pub fn is_payment_token_allowed(payment_token: &Option<String>, allowed: &[String]) -> bool {
+    match payment_token {
+        Some(payment_token) => allowed.contains(payment_token),
+        None => true,
+    }
+}
+
+pub async fn check_payment_token(payment_token: &Option<String>) -> Result<(), ContractError> {
+    if is_payment_token_allowed(payment_token, &payment_tokens().await) {
+        Ok(())
+    } else {
+        Err(ContractError::PaymentTokenNotAllowed(
+            payment_token.clone().unwrap_or_default(),
+        ))
+    }
+}
+
+/// Payment token of a sale of `token_id` that doesn't specify one
+pub async fn default_payment_token(token_id: &str) -> Option<String> {
+    let base_id = match TokenId::parse(token_id, &scarcity_tiers().await) {
+        TokenId::Nft(nft) => nft.base_id,
+        TokenId::Shuffle(shuffle) => shuffle.base_id,
+        TokenId::Token(_) => return None,
+    };
+
+    State::all_attached_royalties(&base_id)
+        .peek()
+        .await
+        .and_then(|royalties| royalties.payment_token)
+}
+
+/// Key of a sale in `State::sales`. The index coming last, keys can't collide whatever the token
+/// ids contain.
+pub fn sale_key(token_id: &str, index: u32) -> String {
+    format!("{}.{}", token_id, index)
+}
+
+pub fn find_scarcity_tier<'a>(tiers: &'a [ScarcityTier], name: &str) -> Option<&'a ScarcityTier> {
+    tiers
+        .iter()
+        .find(|tier| tier.name.eq_ignore_ascii_case(name))
+}
+
+/// Checks `tier` against the tiers listed `before` it. Tier names are segments of the NFT ids,
+/// which are separated by `-`.
+pub fn is_valid_scarcity_tier(tier: &ScarcityTier, before: &[ScarcityTier]) -> bool {
+    !tier.name.is_empty()
+        && tier
+            .name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && tier.editions > 0
+        && tier.max_editions.iter().all(|max| *max >= tier.editions)
+        && find_scarcity_tier(before, &tier.name).is_none()
+}
+
+/// Tiers can't be removed from the settings, since NFTs may have been minted with them
+pub fn validate_scarcity_tiers(
+    tiers: &[ScarcityTier],
+    current: &[ScarcityTier],
+) -> Result<(), ContractError> {
+    if let Some(tier) = current
+        .iter()
+        .find(|tier| find_scarcity_tier(tiers, &tier.name).is_none())
+    {
+        return Err(ContractError::ScarcityTierRemoved(tier.name.clone()));
+    }
+
+    match tiers
+        .iter()
+        .enumerate()
+        .find(|(i, tier)| !is_valid_scarcity_tier(tier, &tiers[..*i]))
+    {
+        Some((_, tier)) => Err(ContractError::InvalidScarcityTier(tier.name.clone())),
+        None => Ok(()),
+    }
+}
+
 pub struct NftId {
     pub edition: u32,
-    pub scarcity: Scarcity,
+    /// Name of the tier
+    pub scarcity: String,
     pub base_id: String,
 }
 
 impl ToString for NftId {
     fn to_string(&self) -> String {
-        format!(
-            "{}-{}-{}",
-            self.edition,
-            self.scarcity.to_string(),
-            self.base_id
-        )
+        format!("{}-{}-{}", self.edition, self.scarcity, self.base_id)
     }
 }
 
@@ -43,10 +138,8 @@ pub enum NftIdParseError {
     Shape,
 }
 
-impl TryFrom<&str> for NftId {
-    type Error = NftIdParseError;
-
-    fn try_from(id: &str) -> Result<Self, Self::Error> {
+impl NftId {
+    pub fn parse(id: &str, tiers: &[ScarcityTier]) -> Result<Self, NftIdParseError> {
         let splited = {
             let mut splited = id.splitn(3, '-');
 
@@ -57,21 +150,19 @@ impl TryFrom<&str> for NftId {
             )
         };
 
-        let scarcity = Scarcity::try_from(splited.1).map_err(|_| NftIdParseError::Scarcity)?;
+        let tier = find_scarcity_tier(tiers, splited.1).ok_or(NftIdParseError::Scarcity)?;
         let edition = splited
             .0
             .parse::<u32>()
             .map_err(|_| NftIdParseError::Edition)?;
 
-        if !matches!(scarcity, Scarcity::Limited(_)) {
-            if edition > Option::<u32>::from(&scarcity).unwrap() {
-                return Err(NftIdParseError::Edition);
-            }
+        if tier.max_editions.is_some_and(|max| edition > max) {
+            return Err(NftIdParseError::Edition);
         }
 
         Ok(Self {
             base_id: splited.2.to_string(),
-            scarcity,
+            scarcity: tier.name.clone(),
             edition,
         })
     }
@@ -109,14 +200,99 @@ pub enum TokenId {
     Token(String),
 }
 
-impl From<&str> for TokenId {
-    fn from(token_id: &str) -> Self {
+impl TokenId {
+    pub fn parse(token_id: &str, tiers: &[ScarcityTier]) -> Self {
         if let Ok(shuffle) = ShuffleId::try_from(token_id) {
             Self::Shuffle(shuffle)
-        } else if let Ok(nft) = NftId::try_from(token_id) {
+        } else if let Ok(nft) = NftId::parse(token_id, tiers) {
             Self::Nft(nft)
         } else {
             Self::Token(token_id.to_string())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nft_ids_against_the_tiers() {
+        let tiers = vec![
+            ScarcityTier::new("MYTHIC", 5, Some(5)),
+            ScarcityTier::new("LIMITED", 1, None),
+        ];
+
+        let nft = NftId::parse("5-mythic-base", &tiers).ok().unwrap();
+        assert_eq!(nft.to_string(), "5-MYTHIC-base");
+        assert!(NftId::parse("6-MYTHIC-base", &tiers).is_err());
+        assert!(NftId::parse("5000-LIMITED-base", &tiers).is_ok());
+        assert!(NftId::parse("1-UNIQUE-base", &tiers).is_err());
+
+        assert!(matches!(
+            TokenId::parse("1-UNIQUE-base", &default_scarcity_tiers()),
+            TokenId::Nft(_)
+        ));
+        assert!(matches!(
+            TokenId::parse("1-UNIQUE-base", &tiers),
+            TokenId::Token(_)
+        ));
+    }
+
+    #[test]
+    fn validates_tiers() {
+        assert!(validate_scarcity_tiers(&default_scarcity_tiers(), &[]).is_ok());
+
+        for tier in [
+            ScarcityTier::new("", 1, None),
+            ScarcityTier::new("Mythic", 5, None),
+            ScarcityTier::new("MY-THIC", 5, None),
+            ScarcityTier::new("MYTHIC", 0, None),
+            ScarcityTier::new("MYTHIC", 5, Some(4)),
+            ScarcityTier::new("UNIQUE", 1, Some(1)),
+        ] {
+            let mut tiers = default_scarcity_tiers();
+            tiers.push(tier.clone());
+
+            assert!(
+                matches!(
+                    validate_scarcity_tiers(&tiers, &[]),
+                    Err(ContractError::InvalidScarcityTier(name)) if name == tier.name
+                ),
+                "{:?}",
+                tier
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_the_current_tiers() {
+        let current = default_scarcity_tiers();
+
+        let mut tiers = current.clone();
+        tiers.push(ScarcityTier::new("MYTHIC", 5, Some(5)));
+        assert!(validate_scarcity_tiers(&tiers, &current).is_ok());
+
+        tiers.retain(|tier| tier.name != "EPIC");
+        assert!(matches!(
+            validate_scarcity_tiers(&tiers, &current),
+            Err(ContractError::ScarcityTierRemoved(name)) if name == "EPIC"
+        ));
+    }
+
+    #[test]
+    fn allows_the_default_token_and_the_listed_ones() {
+        let allowed = vec!["GOLD".to_string()];
+
+        assert!(is_payment_token_allowed(&None, &[]));
+        assert!(is_payment_token_allowed(
+            &Some("GOLD".to_string()),
+            &allowed
+        ));
+        assert!(!is_payment_token_allowed(
+            &Some("SILVER".to_string()),
+            &allowed
+        ));
+        assert!(!is_payment_token_allowed(&Some("GOLD".to_string()), &[]));
+    }
+}
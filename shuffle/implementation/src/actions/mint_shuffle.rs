@@ -101,6 +101,8 @@ impl AsyncActionable for MintShuffle {
             base_id: self.base_id,
             prefix: Some(prefix.to_string()),
             qty: Balance::new(total_editions),
+            max_supply: None,
+            target: None,
         });
 
         foreign_caller
@@ -19,7 +19,7 @@ use warp_shuffle::{
 use crate::{
     actions::AsyncActionable,
     contract_utils::foreign_call::{ForeignContractCaller, ForeignContractState},
-    utils::{splited_nft_id, NftId, Rng, Scarcity},
+    utils::{NftId, Rng},
 };
 use crate::{contract_utils::js_imports::Vrf, utils::get_all_nfts_ids};
 
@@ -57,7 +57,7 @@ fn get_available_nfts(
                 .clone()
                 .into_iter()
                 .filter(|id| {
-                    let edition = splited_nft_id(id).unwrap().edition;
+                    let edition = NftId::try_from(id.as_str()).map_or(0, |nft_id| nft_id.edition);
                     edition >= 1 && edition <= 10u32.pow(i as u32)
                 })
                 .collect::<Vec<String>>()
@@ -188,6 +188,7 @@ impl AsyncActionable for OpenShuffle {
                 token_id: Some(self.shuffle_id),
                 owner: Some(owner.clone()),
                 qty: Balance::new(1),
+                reason: Some("Shuffle opened".to_string()),
             }),
             // TODO: Interact with the Scarcity contract to pay for the NFT. As it is now
             // (interacting directly with the ERC1155 contract), the royalties are bypassed.
@@ -49,53 +49,12 @@ impl Rng {
     }
 }
 
-#[derive(Debug)]
-pub enum Scarcity {
-    Unique,
-    Legendary,
-    Epic,
-    Rare,
-}
-
-impl TryFrom<&str> for Scarcity {
-    type Error = ();
-
-    fn try_from(scarcity_raw: &str) -> Result<Self, Self::Error> {
-        match scarcity_raw.to_lowercase().as_str() {
-            "unique" => Ok(Self::Unique),
-            "legendary" => Ok(Self::Legendary),
-            "epic" => Ok(Self::Epic),
-            "rare" => Ok(Self::Rare),
-            _ => Err(()),
-        }
-    }
-}
-
-impl From<&Scarcity> for u32 {
-    fn from(scarcity: &Scarcity) -> Self {
-        match scarcity {
-            Scarcity::Unique => 1,
-            Scarcity::Legendary => 10,
-            Scarcity::Epic => 100,
-            Scarcity::Rare => 1000,
-        }
-    }
-}
-
-impl ToString for Scarcity {
-    fn to_string(&self) -> String {
-        match self {
-            Scarcity::Unique => "UNIQUE".to_string(),
-            Scarcity::Legendary => "LEGENDARY".to_string(),
-            Scarcity::Epic => "EPIC".to_string(),
-            Scarcity::Rare => "RARE".to_string(),
-        }
-    }
-}
-
+/// NOTE: The editions an NFT can have depend on its scarcity tier, which is configured in the
+/// Scarcity contract, so they aren't checked here.
 pub struct NftId {
     pub base_id: String,
-    pub scarcity: Scarcity,
+    /// Name of the scarcity tier
+    pub scarcity: String,
     pub edition: u32,
 }
 
@@ -113,51 +72,18 @@ impl TryFrom<&str> for NftId {
             )
         };
 
-        let scarcity = Scarcity::try_from(splited.1)?;
-        let edition = splited.0.parse::<u32>().unwrap_or(0);
-
-        if edition > u32::from(&scarcity) {
-            Err(())
-        } else {
-            Ok(NftId {
-                base_id: splited.2.to_string(),
-                scarcity,
-                edition,
-            })
-        }
+        let edition = splited.0.parse::<u32>().map_err(|_| ())?;
+
+        Ok(NftId {
+            base_id: splited.2.to_string(),
+            scarcity: splited.1.to_uppercase(),
+            edition,
+        })
     }
 }
 
 impl ToString for NftId {
     fn to_string(&self) -> String {
-        format!(
-            "{}-{}-{}",
-            self.edition,
-            self.scarcity.to_string(),
-            self.base_id
-        )
-    }
-}
-
-/// 1-UNIQUE-BASE_ID
-pub fn splited_nft_id(id: &str) -> Option<NftId> {
-    let splited = {
-        let mut splited = id.splitn(3, '-');
-
-        (splited.next()?, splited.next()?, splited.next()?)
-    };
-
-    let scarcity = Scarcity::try_from(splited.1).ok()?;
-
-    let edition = splited.0.parse::<u32>().unwrap_or(0);
-
-    if edition > u32::from(&scarcity) {
-        None
-    } else {
-        Some(NftId {
-            base_id: splited.2.to_string(),
-            scarcity,
-            edition,
-        })
+        format!("{}-{}-{}", self.edition, self.scarcity, self.base_id)
     }
 }
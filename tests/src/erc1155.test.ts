@@ -1,5 +1,6 @@
 import { it, expect, beforeAll, afterAll } from "vitest";
 import Arlocal from "arlocal";
+import Arweave from "arweave";
 import { Contract, LoggerFactory, Warp, WarpFactory } from "warp-contracts";
 import { Wallet } from "warp-contracts/lib/types/contract/testing/Testing";
 import { DeployPlugin } from "warp-contracts-plugin-deploy";
@@ -191,6 +192,70 @@ it("fail if contract is already initialized", async () => {
     });
 });
 
+it("should reject an invalid initial state", async () => {
+    const initState: Erc1155.Parameters = {
+        name: "TEST-ERC1155-INVALID",
+        initialState: {
+            tickerNonce: 0,
+            settings: {
+                defaultToken: "EUR",
+                paused: false,
+                superOperators: [op.address],
+                operators: [user.address, user.address],
+                proxies: [],
+                allowFreeTransfer: true,
+            },
+            tokens: {
+                DOL: {
+                    ticker: "DOL",
+                    balances: {
+                        [op.address]: "0",
+                    },
+                },
+                "DOL.USD": {
+                    ticker: "DOL.USD",
+                    balances: {
+                        [op.address]: "10",
+                    },
+                    maxSupply: "5",
+                },
+            },
+            approvals: {},
+        },
+        canEvolve: false,
+    };
+
+    const invalidContract = warp
+        .contract<Erc1155.Parameters>(
+            (await deployContract(warp, op.jwk, "erc1155", initState)).contractTxId,
+        )
+        .setEvaluationOptions({
+            useKVStorage: true,
+            internalWrites: true,
+            mineArLocalBlocks: false,
+        })
+        .connect(op.jwk);
+    const invalidInteract = createInteractor<Erc1155.Action, Erc1155.ContractError>(
+        warp,
+        invalidContract,
+        op.jwk,
+    );
+
+    expectError(await invalidInteract({ function: "initialize" }), {
+        kind: "InvalidInitialState",
+        data: [
+            { kind: "DefaultTokenNotFound", data: "EUR" },
+            { kind: "ZeroBalance", data: { tokenId: "DOL", address: op.address } },
+            { kind: "InvalidTokenId", data: "DOL.USD" },
+            { kind: "MaxSupplyExceeded", data: "DOL.USD" },
+            { kind: "DuplicateEntry", data: { field: "operators", address: user.address } },
+        ],
+    });
+
+    const state = (await invalidContract.readState()).cachedValue.state;
+    expect(state.initialState).toBeTruthy();
+});
+
 it("should not accept write interactions when paused", async () => {
     expectOk(await interact({ function: "configure", paused: true }));
 
@@ -314,11 +379,52 @@ it("should burn some tokens", async () => {
     }
 });
 
+it("should not mint tokens with an invalid id", async () => {
+    for (const baseId of ["", "NFT.1", "NFT 1", "NFT-é", "A".repeat(129)]) {
+        expectError(await interact({ function: "mint", baseId, qty: "1" }), {
+            kind: "InvalidTokenId",
+            data: baseId,
+        });
+    }
+
+    expectError(await interact({ function: "mint", prefix: "NFT", baseId: "a.b", qty: "1" }), {
+        kind: "InvalidTokenId",
+        data: "NFT-a.b",
+    });
+});
+
+it("should only let configurators mint under a reserved prefix", async () => {
+    expectOk(
+        await interact({
+            function: "configure",
+            tokenIdPolicy: {
+                allowedSymbols: "-_",
+                maxLength: 128,
+                reservedPrefixes: ["SHUFFLE"],
+            },
+        }),
+    );
+
+    expectOk(await interact({ function: "grantRole", role: "minter", account: user.address }));
+
+    const mint = { function: "mint", prefix: "SHUFFLE", baseId: "RESERVED", qty: "1" } as const;
+
+    expectError(await interact(mint, { wallet: user.jwk }), {
+        kind: "InvalidTokenId",
+        data: "SHUFFLE-RESERVED",
+    });
+
+    expectOk(await interact(mint));
+
+    expectOk(await interact({ function: "revokeRole", role: "minter", account: user.address }));
+});
+
 it("should throw when non-op try to burn tokens", async () => {
     const burnInteraction = await interact(
         {
             function: "burn",
             tokenId: "DOL",
+            owner: op.address,
             qty: "1",
         },
         { wallet: user.jwk },
@@ -330,6 +436,657 @@ it("should throw when non-op try to burn tokens", async () => {
     });
 });
 
+it("should let holders and their approved operators burn tokens", async () => {
+    const tokenId = "BURN";
+    const burn = { function: "burn", tokenId, owner: user.address, qty: "1" } as const;
+
+    expectOk(await interact({ function: "mint", baseId: tokenId, qty: "10" }));
+    expectOk(await interact({ function: "transfer", tokenId, target: user.address, qty: "10" }));
+
+    expectOk(await interact({ function: "burn", tokenId, qty: "1" }, { wallet: user.jwk }));
+
+    expectError(await interact(burn, { wallet: bank.jwk }), {
+        kind: "UnauthorizedAddress",
+        data: bank.address,
+    });
+    expectOk(
+        await interact(
+            { function: "setApprovalForAll", operator: bank.address, approved: true },
+            { wallet: user.jwk },
+        ),
+    );
+    expectOk(await interact(burn, { wallet: bank.jwk }));
+
+    // Burners need a reason to burn tokens they aren't approved for
+    expectError(await interact(burn), { kind: "BurnReasonRequired" });
+    expectError(await interact({ ...burn, reason: " " }), { kind: "BurnReasonRequired" });
+    expectOk(await interact({ ...burn, reason: "Fraudulent purchase" }));
+
+    const balance = await view({ function: "balanceOf", tokenId, target: user.address });
+    expectOk(balance);
+    expect(balance.result.balance).toBe("7");
+
+    expectOk(
+        await interact(
+            { function: "setApprovalForAll", operator: bank.address, approved: false },
+            { wallet: user.jwk },
+        ),
+    );
+});
+
+it("should migrate super-operators into every role", async () => {
+    const settings = await view({ function: "readSettings" });
+    expectOk(settings);
+
+    for (const members of Object.values(settings.result.roles)) {
+        expect(members).toContain(op.address);
+    }
+});
+
+it("should grant, check, revoke and renounce roles", async () => {
+    const hasBurnerRole = async () => {
+        const response = await view({ function: "hasRole", role: "burner", account: user.address });
+        expectOk(response);
+        return response.result.hasRole;
+    };
+
+    expect(await hasBurnerRole()).toBe(false);
+
+    expectOk(await interact({ function: "grantRole", role: "burner", account: user.address }));
+    expect(await hasBurnerRole()).toBe(true);
+
+    // Being a burner doesn't give access to other roles
+    expectError(
+        await interact(
+            { function: "grantRole", role: "burner", account: bank.address },
+            { wallet: user.jwk },
+        ),
+        { kind: "UnauthorizedAddress", data: user.address },
+    );
+    expectError(await interact({ function: "configure", paused: true }, { wallet: user.jwk }), {
+        kind: "UnauthorizedConfiguration",
+    });
+
+    expectOk(await interact({ function: "renounceRole", role: "burner" }, { wallet: user.jwk }));
+    expect(await hasBurnerRole()).toBe(false);
+
+    expectOk(await interact({ function: "grantRole", role: "burner", account: user.address }));
+    expectOk(await interact({ function: "revokeRole", role: "burner", account: user.address }));
+    expect(await hasBurnerRole()).toBe(false);
+});
+
+it("should not remove the last configurator", async () => {
+    expectOk(await interact({ function: "revokeRole", role: "configurator", account: bank.address }));
+
+    expectError(await interact({ function: "renounceRole", role: "configurator" }), {
+        kind: "CannotRemoveLastConfigurator",
+    });
+
+    expectOk(await interact({ function: "grantRole", role: "configurator", account: bank.address }));
+});
+
+it("should still derive the roles from the legacy operator lists", async () => {
+    const hasRole = async (role: "minter" | "configurator", account = user.address) => {
+        const response = await view({ function: "hasRole", role, account });
+        expectOk(response);
+        return response.result.hasRole;
+    };
+
+    expectOk(await interact({ function: "configure", operators: [user.address] }));
+    expect(await hasRole("minter")).toBe(true);
+    expect(await hasRole("configurator")).toBe(false);
+
+    expectError(await interact({ function: "configure", superOperators: [] }), {
+        kind: "CannotRemoveLastConfigurator",
+    });
+
+    // Only the changes of the lists are applied, keeping the roles granted and revoked since
+    expectOk(await interact({ function: "revokeRole", role: "minter", account: user.address }));
+    expectOk(await interact({ function: "grantRole", role: "configurator", account: "addess-2" }));
+    expectOk(await interact({ function: "configure", operators: [user.address, "addess-1"] }));
+    expect(await hasRole("minter")).toBe(false);
+    expect(await hasRole("minter", "addess-1")).toBe(true);
+    expect(await hasRole("configurator", "addess-2")).toBe(true);
+
+    expectOk(await interact({ function: "configure", operators: [] }));
+    expect(await hasRole("minter")).toBe(false);
+    expect(await hasRole("minter", "addess-1")).toBe(false);
+
+    expectOk(await interact({ function: "revokeRole", role: "configurator", account: "addess-2" }));
+});
+
+it("should list the holders of a token page by page", async () => {
+    const holders: [string, string][] = [];
+    let after: string | undefined = undefined;
+
+    for (;;) {
+        const response = await view({ function: "getHolders", tokenId: "DOL", after, limit: 30 });
+        expectOk(response);
+        expect(response.result.holders.length).toBeLessThanOrEqual(30);
+
+        holders.push(...response.result.holders);
+        expect(response.result.holdersCount).toBeGreaterThanOrEqual(holders.length);
+
+        if (!response.result.next) {
+            expect(response.result.holdersCount).toBe(holders.length);
+            break;
+        }
+        after = response.result.next;
+    }
+
+    const addresses = holders.map(([address]) => address);
+    expect(addresses).toEqual([...addresses].sort());
+    expect(addresses).toContain("addess-42");
+
+    expectError(await view({ function: "getHolders", tokenId: "UNKNOWN" }), {
+        kind: "TokenNotFound",
+        data: "UNKNOWN",
+    });
+    for (const limit of [0, 1001]) {
+        expectError(await view({ function: "getHolders", tokenId: "DOL", limit }), {
+            kind: "InvalidLimit",
+            data: limit,
+        });
+    }
+});
+
+it("should not overflow balances", async () => {
+    const u128Max = (2n ** 128n - 1n).toString();
+
+    expectOk(await interact({ function: "mint", baseId: "HUGE", qty: u128Max }));
+
+    const balance = await view({ function: "balanceOf", tokenId: "HUGE", target: op.address });
+    expectOk(balance);
+    expect(balance.result.balance).toBe(u128Max);
+
+    expectError(await interact({ function: "mint", baseId: "HUGE", qty: "1" }), {
+        kind: "BalanceOverflow",
+    });
+});
+
+it("should not mint more than the max supply", async () => {
+    const mint = { function: "mint", baseId: "CAPPED", qty: "1" } as const;
+
+    expectOk(await interact({ ...mint, maxSupply: "2" }));
+    expectError(await interact({ ...mint, maxSupply: "3" }), {
+        kind: "MaxSupplyIsImmutable",
+        data: "CAPPED",
+    });
+    expectError(await interact({ ...mint, qty: "2" }), {
+        kind: "MaxSupplyExceeded",
+        data: "CAPPED",
+    });
+    expectOk(await interact(mint));
+    expectError(await interact(mint), { kind: "MaxSupplyExceeded", data: "CAPPED" });
+
+    const response = await view({ function: "getToken", tokenId: "CAPPED" });
+    expectOk(response);
+    expect(response.result[1].maxSupply).toEqual("2");
+});
+
+it("should approve an operator with a permit signed by the owner", async () => {
+    const permit = {
+        owner: user.address,
+        operator: op.address,
+        approved: true,
+        nonce: 0,
+        deadline: 1_000_000,
+    };
+    const sign = async (permit: typeof permit) => {
+        const message = [
+            "permit",
+            contractId,
+            permit.owner,
+            permit.operator,
+            permit.approved,
+            permit.nonce,
+            permit.deadline,
+        ].join(":");
+        const signature = await warp.arweave.crypto.sign(user.jwk, new TextEncoder().encode(message));
+
+        return {
+            function: "permit",
+            ...permit,
+            publicKey: user.jwk.n,
+            signature: Arweave.utils.bufferTob64Url(signature),
+        } as const;
+    };
+
+    const nonce = await view({ function: "getPermitNonce", owner: user.address });
+    expectOk(nonce);
+    expect(nonce.result.nonce).toBe(0);
+
+    expectError(await interact({ ...(await sign(permit)), approved: false }), {
+        kind: "InvalidPermitSignature",
+    });
+    expectError(await interact(await sign({ ...permit, deadline: 0 })), {
+        kind: "PermitExpired",
+    });
+
+    // Submitted by `op` on behalf of `user`
+    const signedPermit = await sign(permit);
+    expectOk(await interact(signedPermit));
+    expectError(await interact(signedPermit), { kind: "InvalidPermitNonce", data: 1 });
+
+    const approval = await view({
+        function: "isApprovedForAll",
+        owner: user.address,
+        operator: op.address,
+    });
+    expectOk(approval);
+    expect(approval.result.approved).toBe(true);
+
+    expectOk(await interact(await sign({ ...permit, approved: false, nonce: 1 })));
+});
+
+it("should only let proxies perform the actions they are allowed to", async () => {
+    // A wallet acting as its own proxy is enough to exercise the permission check
+    expectOk(await interact({ function: "configure", proxies: [user.address] }));
+
+    let proxies = await view({ function: "getProxies" });
+    expectOk(proxies);
+    // Proxies can't do anything until they are given permissions
+    expect(proxies.result[user.address]).toEqual([]);
+    expectError(
+        await interact(
+            { function: "transfer", target: op.address, qty: "1" },
+            { wallet: user.jwk },
+        ),
+        { kind: "UnauthorizedProxyAction", data: "transfer" },
+    );
+
+    expectOk(
+        await interact({
+            function: "setProxyPermissions",
+            proxy: user.address,
+            permissions: ["transfer"],
+        }),
+    );
+    // Configuring the proxies again keeps the permissions of the existing ones
+    expectOk(await interact({ function: "configure", proxies: [user.address, bank.address] }));
+    proxies = await view({ function: "getProxies" });
+    expectOk(proxies);
+    expect(proxies.result[user.address]).toEqual(["transfer"]);
+    expect(proxies.result[bank.address]).toEqual([]);
+
+    expectError(
+        await interact(
+            { function: "setApprovalForAll", operator: op.address, approved: true },
+            { wallet: user.jwk },
+        ),
+        { kind: "UnauthorizedProxyAction", data: "approve" },
+    );
+    expectError(
+        await interact(
+            {
+                function: "batch",
+                actions: [
+                    { function: "transfer", target: op.address, qty: "1" },
+                    { function: "burn", qty: "1" },
+                ],
+            },
+            { wallet: user.jwk },
+        ),
+        { kind: "UnauthorizedProxyAction", data: "burn" },
+    );
+    expectOk(
+        await interact(
+            { function: "transfer", target: op.address, qty: "1" },
+            { wallet: user.jwk },
+        ),
+    );
+
+    expectOk(await interact({ function: "configure", proxies: [] }));
+});
+
+/** Receiver contract recording its notifications, which rejects them when `reject` is set */
+const RECEIVER_SOURCE = `
+export async function handle(state, action) {
+    const input = action.input;
+
+    if (input.function === "register" || input.function === "unregister") {
+        const result = await SmartWeave.contracts.write(input.erc1155, {
+            function: input.function + "Receiver",
+        });
+        if (result.type !== "ok") {
+            throw new ContractError(result.errorMessage);
+        }
+    } else if (input.function === "setReject") {
+        state.reject = input.reject;
+    } else if (input.function === "onReceived") {
+        if (state.reject) {
+            throw new ContractError("Rejected");
+        }
+        const { function: _, ...notification } = input;
+        state.received.push(notification);
+    }
+
+    return { state };
+}
+`;
+
+it("should notify registered receiver contracts", async () => {
+    const isReceiver = async (address: string) => {
+        const response = await view({ function: "isReceiver", address });
+        expectOk(response);
+        return response.result.registered;
+    };
+
+    // Wallets can't register, they would reject every transfer made to them
+    expectError(await interact({ function: "registerReceiver" }, { wallet: user.jwk }), {
+        kind: "UnauthorizedAddress",
+        data: user.address,
+    });
+    expect(await isReceiver(user.address)).toBe(false);
+
+    const { contractTxId: receiverId } = await warp.deploy({
+        wallet: op.jwk,
+        initState: JSON.stringify({ reject: false, received: [] }),
+        src: RECEIVER_SOURCE,
+    });
+    const receiver = warp
+        .contract<{ reject: boolean; received: unknown[] }>(receiverId)
+        .setEvaluationOptions({ useKVStorage: true, internalWrites: true })
+        .connect(op.jwk);
+    const receiverInteract = async (input: Record<string, unknown>) => {
+        await receiver.writeInteraction(input, { strict: true });
+        await warp.testing.mineBlock();
+    };
+    const received = async () => (await receiver.readState()).cachedValue.state.received;
+
+    await receiverInteract({ function: "register", erc1155: contractId });
+    expect(await isReceiver(receiverId)).toBe(true);
+
+    expectOk(await interact({ function: "transfer", target: receiverId, qty: "1" }));
+    expectOk(
+        await interact({ function: "mint", baseId: "RECEIVED", qty: "2", target: receiverId }),
+    );
+    expect(await received()).toEqual([
+        { operator: op.address, from: op.address, tokenId: "DOL", qty: "1" },
+        { operator: op.address, from: "", tokenId: "RECEIVED", qty: "2" },
+    ]);
+
+    // Rejections roll the transfers and mints back
+    await receiverInteract({ function: "setReject", reject: true });
+    expectError(await interact({ function: "transfer", target: receiverId, qty: "1" }), {
+        kind: "ReceiverRejected",
+        data: receiverId,
+    });
+    expectError(
+        await interact({ function: "mint", baseId: "RECEIVED", qty: "1", target: receiverId }),
+        { kind: "ReceiverRejected", data: receiverId },
+    );
+
+    const balance = await view({ function: "balanceOf", tokenId: "DOL", target: receiverId });
+    expectOk(balance);
+    expect(balance.result.balance).toEqual("1");
+    const token = await view({ function: "getToken", tokenId: "RECEIVED" });
+    expectOk(token);
+    expect(token.result[1].balances[receiverId]).toEqual("2");
+
+    await receiverInteract({ function: "unregister", erc1155: contractId });
+    expect(await isReceiver(receiverId)).toBe(false);
+    expectOk(await interact({ function: "transfer", target: receiverId, qty: "1" }));
+});
+
+it("should read balances at past snapshots", async () => {
+    const tokenId = "SNAP";
+    const transfer = { function: "transfer", tokenId, target: user.address } as const;
+
+    const takeSnapshot = async () => {
+        expectOk(await interact({ function: "snapshot" }));
+
+        const snapshots = await view({ function: "getSnapshots" });
+        expectOk(snapshots);
+        return snapshots.result[snapshots.result.length - 1].id;
+    };
+    const balanceAt = async (target: string, snapshotId: number) => {
+        const response = await view({ function: "balanceOfAt", tokenId, target, snapshotId });
+        expectOk(response);
+        return response.result.balance;
+    };
+
+    expectOk(await interact({ function: "mint", baseId: tokenId, qty: "10" }));
+    const first = await takeSnapshot();
+
+    expectOk(await interact({ ...transfer, qty: "4" }));
+    const second = await takeSnapshot();
+
+    expectOk(await interact({ ...transfer, qty: "1" }));
+
+    expect(await balanceAt(op.address, first)).toBe("10");
+    expect(await balanceAt(user.address, first)).toBe("0");
+    expect(await balanceAt(op.address, second)).toBe("6");
+    expect(await balanceAt(user.address, second)).toBe("4");
+
+    expectError(
+        await view({ function: "balanceOfAt", tokenId, target: op.address, snapshotId: 0 }),
+        { kind: "SnapshotNotFound", data: 0 },
+    );
+});
+
+it("should airdrop tokens to many recipients", async () => {
+    const tokenId = "DROP";
+    const airdrop = {
+        function: "airdrop",
+        tokenId,
+        recipients: [
+            [user.address, "10"],
+            [op.address, "5"],
+            [bank.address, "20"],
+            ["addess-1", "0"],
+            ["addess.2", "1"],
+        ],
+    } as const;
+
+    expectOk(await interact({ function: "mint", baseId: tokenId, qty: "100" }));
+
+    expectError(await interact(airdrop), { kind: "TransferFromAndToCannotBeEqual" });
+    expectError(
+        await interact({ ...airdrop, recipients: [[user.address, "101"]], skipInvalid: true }),
+        { kind: "OwnerBalanceNotEnough", data: op.address },
+    );
+
+    const summary = await view({ ...airdrop, skipInvalid: true });
+    expectOk(summary);
+    expect(summary.result).toEqual([
+        { recipient: user.address, qty: "10", error: null },
+        { recipient: op.address, qty: "5", error: { kind: "TransferFromAndToCannotBeEqual" } },
+        { recipient: bank.address, qty: "20", error: null },
+        {
+            recipient: "addess-1",
+            qty: "0",
+            error: { kind: "TransferAmountMustBeHigherThanZero" },
+        },
+        {
+            recipient: "addess.2",
+            qty: "1",
+            error: { kind: "InvalidAddress", data: "addess.2" },
+        },
+    ]);
+
+    expectOk(await interact({ ...airdrop, skipInvalid: true }));
+
+    for (const [target, balance] of [
+        [op.address, "70"],
+        [user.address, "10"],
+        [bank.address, "20"],
+    ]) {
+        const response = await view({ function: "balanceOf", tokenId, target });
+        expectOk(response);
+        expect(response.result.balance).toBe(balance);
+    }
+});
+
+it("should not let frozen accounts transfer or burn", async () => {
+    expectOk(await interact({ function: "freeze", account: bank.address }));
+
+    const frozenAccounts = await view({ function: "getFrozenAccounts" });
+    expectOk(frozenAccounts);
+    expect(frozenAccounts.result).toEqual([bank.address]);
+
+    expectError(
+        await interact(
+            { function: "transfer", target: user.address, qty: "1" },
+            { wallet: bank.jwk },
+        ),
+        { kind: "AccountIsFrozen", data: bank.address },
+    );
+    expectError(
+        await interact({ function: "burn", owner: bank.address, qty: "1", reason: "Frozen" }),
+        { kind: "AccountIsFrozen", data: bank.address },
+    );
+
+    // Frozen accounts can still receive tokens
+    expectOk(await interact({ function: "transfer", target: bank.address, qty: "1" }));
+
+    expectOk(await interact({ function: "unfreeze", account: bank.address }));
+    expectOk(
+        await interact(
+            { function: "transfer", target: op.address, qty: "1" },
+            { wallet: bank.jwk },
+        ),
+    );
+});
+
+it("should not transfer or burn a paused token", async () => {
+    expectError(await interact({ function: "pauseToken", tokenId: "UNKNOWN" }), {
+        kind: "TokenNotFound",
+        data: "UNKNOWN",
+    });
+    expectError(await interact({ function: "pauseToken", tokenId: "DOL" }, { wallet: user.jwk }), {
+        kind: "UnauthorizedAddress",
+        data: user.address,
+    });
+
+    expectOk(await interact({ function: "pauseToken", tokenId: "DOL" }));
+
+    const pausedTokens = await view({ function: "getPausedTokens" });
+    expectOk(pausedTokens);
+    expect(pausedTokens.result).toEqual(["DOL"]);
+
+    expectError(await interact({ function: "transfer", target: user.address, qty: "1" }), {
+        kind: "TokenIsPaused",
+        data: "DOL",
+    });
+    expectError(await interact({ function: "burn", qty: "1" }), {
+        kind: "TokenIsPaused",
+        data: "DOL",
+    });
+
+    expectOk(await interact({ function: "unpauseToken", tokenId: "DOL" }));
+    expectOk(await interact({ function: "transfer", target: user.address, qty: "1" }));
+});
+
+it("should simulate actions without persisting anything", async () => {
+    const tokenId = "SIM";
+    const transfer = { function: "transfer", tokenId, target: user.address, qty: "4" } as const;
+
+    expectOk(await interact({ function: "mint", baseId: tokenId, qty: "10" }));
+
+    const simulation = await view({ function: "simulate", action: transfer });
+    expectOk(simulation);
+    expect(simulation.result.error).toBeNull();
+    expect(simulation.result.foreignWrites).toEqual([]);
+    expect(simulation.result.changes).toEqual(
+        expect.arrayContaining([
+            { key: `.tokens.${tokenId}.balances.${op.address}`, before: "10", after: "6" },
+            { key: `.tokens.${tokenId}.balances.${user.address}`, before: null, after: "4" },
+        ]),
+    );
+
+    const balance = await view({ function: "balanceOf", tokenId, target: user.address });
+    expectOk(balance);
+    expect(balance.result.balance).toBe("0");
+
+    const failure = await view({ function: "simulate", action: { ...transfer, qty: "11" } });
+    expectOk(failure);
+    expect(failure.result.error).toEqual({ kind: "OwnerBalanceNotEnough", data: op.address });
+    expect(failure.result.changes).toEqual([]);
+
+    const read = await view({
+        function: "simulate",
+        action: { function: "balanceOf", tokenId, target: op.address },
+    });
+    expectOk(read);
+    expect(read.result.changes).toEqual([]);
+    expect(read.result.response).toEqual({ balanceOf: { balance: "10", target: op.address } });
+
+    const nested = await view({
+        function: "simulate",
+        action: { function: "simulate", action: transfer },
+    });
+    expectOk(nested);
+    expect(nested.result.error).toEqual({ kind: "ForbiddenNestedSimulation" });
+});
+
+it("should batch reads and writes atomically or on a best-effort basis", async () => {
+    const tokenId = "BATCH";
+    const balanceOf = { function: "balanceOf", tokenId, target: user.address } as const;
+    const actions = [
+        { function: "transfer", tokenId, target: user.address, qty: "3" },
+        balanceOf,
+        { function: "transfer", tokenId, target: user.address, qty: "100" },
+    ] as const;
+
+    expectOk(await interact({ function: "mint", baseId: tokenId, qty: "10" }));
+
+    expectError(await interact({ function: "batchV2", actions: [...actions] }), {
+        kind: "BatchActionFailed",
+        data: { index: 2, error: { kind: "OwnerBalanceNotEnough", data: op.address } },
+    });
+
+    const balanceAfterAtomic = await view(balanceOf);
+    expectOk(balanceAfterAtomic);
+    expect(balanceAfterAtomic.result.balance).toBe("0");
+
+    const bestEffort = { function: "batchV2", actions: [...actions], mode: "bestEffort" } as const;
+
+    const results = await view(bestEffort);
+    expectOk(results);
+    expect(results.result).toEqual([
+        { kind: "write" },
+        { kind: "read", data: { balanceOf: { balance: "3", target: user.address } } },
+        { kind: "error", data: { kind: "OwnerBalanceNotEnough", data: op.address } },
+    ]);
+
+    expectOk(await interact(bestEffort));
+
+    const balanceAfterBestEffort = await view(balanceOf);
+    expectOk(balanceAfterBestEffort);
+    expect(balanceAfterBestEffort.result.balance).toBe("3");
+
+    // Batched actions can't change the contract's parameters
+    expectOk(await interact({ function: "configure", canEvolve: true }));
+    expectError(
+        await interact({ function: "batchV2", actions: [{ function: "evolve", value: "" }] }),
+        {
+            kind: "BatchActionFailed",
+            data: { index: 0, error: { kind: "CannotChangeParametersInBatch" } },
+        },
+    );
+    expectOk(await interact({ function: "configure", canEvolve: false }));
+
+    // Actions that must run at the top level can't be batched
+    expectError(await interact({ function: "batchV2", actions: [bestEffort] }), {
+        kind: "ForbiddenNestedBatch",
+    });
+    expectError(
+        await interact({
+            function: "batchV2",
+            actions: [{ function: "asDirectCaller", action: actions[0] }],
+        }),
+        { kind: "ForbiddenActionInBatch" },
+    );
+    expectError(
+        await interact({
+            function: "batch",
+            actions: [{ function: "asDirectCaller", action: actions[0] }],
+        }),
+        { kind: "ForbiddenActionInBatch" },
+    );
+});
+
 // NOTE: Errors are not correctly stored with Pianity's Warp fork yet
 // it("publish an invalid interaction with strict:false and read the state", async () => {
 //     // This interaction is invalid because `mint` requires being an operator and `user` isn't
@@ -85,7 +85,6 @@ beforeAll(async () => {
                     balances: {
                         [op.address]: `${opBaseBalance}`,
                         [user.address]: `${userBaseBalance}`,
-                        [user2.address]: "0",
                         [bank.address]: "9999999999999999999",
                         // [bank.address]: "100",
                     },
@@ -183,6 +182,66 @@ it("initialize Lock", async () => {
     expect(stateAfter.initialState).toBeNull();
 });
 
+it("should reject invalid vault entries in the initial state", async () => {
+    const initState: Lock.Parameters = {
+        name: "TEST-LOCK-INVALID",
+        initialState: {
+            settings: {
+                superOperators: [op.address],
+                operators: [],
+                erc1155: erc1155TxId,
+                paused: false,
+            },
+            vault: {
+                [user.address]: [
+                    {
+                        type: "cliff",
+                        tokenId: "DOL",
+                        from: op.address,
+                        qty: "0",
+                        at: 0,
+                        duration: 1,
+                    },
+                    {
+                        type: "linear",
+                        tokenId: "DOL",
+                        from: op.address,
+                        qty: "10",
+                        at: 0,
+                        duration: 1,
+                        unlocked: "20",
+                    },
+                ],
+            },
+        },
+        canEvolve: false,
+    };
+
+    const invalidContract = warp
+        .contract<Lock.Parameters>(
+            (await deployContract(warp, op.jwk, "lock", initState)).contractTxId,
+        )
+        .setEvaluationOptions({
+            internalWrites: true,
+            throwOnInternalWriteError: false,
+            mineArLocalBlocks: false,
+        })
+        .connect(op.jwk);
+    const invalidInteract = createInteractor<Lock.Action, Lock.ContractError>(
+        warp,
+        invalidContract,
+        op.jwk,
+    );
+
+    expectError(await invalidInteract({ function: "initialize" }), {
+        kind: "InvalidInitialState",
+        data: [
+            { kind: "ZeroQty", data: { owner: user.address, index: 0 } },
+            { kind: "UnlockedExceedsQty", data: { owner: user.address, index: 1 } },
+        ],
+    });
+});
+
 it("configure Lock as proxy of Erc1155", async () => {
     const settings = await erc1155View({ function: "readSettings" });
     expectOk(settings);
@@ -191,9 +250,21 @@ it("configure Lock as proxy of Erc1155", async () => {
         await erc1155Interact({
             function: "configure",
             proxies: [...settings.result.proxies, lockTxId],
-            superOperators: [...settings.result.superOperators, lockTxId],
         }),
     );
+
+    expectOk(await erc1155Interact({ function: "grantRole", role: "minter", account: lockTxId }));
+    expectOk(
+        await erc1155Interact({
+            function: "setProxyPermissions",
+            proxy: lockTxId,
+            permissions: ["transfer"],
+        }),
+    );
+
+    const proxies = await erc1155View({ function: "getProxies" });
+    expectOk(proxies);
+    expect(proxies.result[lockTxId]).toEqual(["transfer"]);
 });
 
 it("correctly set balance to 0 after emptying it using erc1155 directly", async () => {
@@ -267,6 +338,59 @@ it("should execute a cliff transferLock correctly", async () => {
     }
 }, 60_000);
 
+it("should simulate a transferLocked without locking anything", async () => {
+    const target = user.address;
+    const transferLocked = {
+        function: "transferLocked",
+        tokenId: "DOL",
+        target,
+        duration: 2,
+        qty: "10",
+        method: "cliff",
+    } as const;
+
+    const simulation = await lockView({ function: "simulate", action: transferLocked });
+    expectOk(simulation);
+    expect(simulation.result.error).toBeNull();
+    expect(simulation.result.changes.map(({ key }) => key)).toEqual([`.vault.${target}`]);
+    expect(simulation.result.foreignWrites).toHaveLength(1);
+    expect(simulation.result.foreignWrites[0].contractId).toBe(erc1155TxId);
+
+    const vault = await lockView({ function: "getVault", owner: target });
+    expectError(vault, { kind: "OwnerHasNoVault", data: target });
+});
+
+it("should report the result of each action of a best-effort batch", async () => {
+    const results = await lockView({
+        function: "batchV2",
+        mode: "bestEffort",
+        actions: [
+            { function: "getVault", owner: user2.address },
+            { function: "getVault", owner: user.address },
+        ],
+    });
+    expectOk(results);
+    expect(results.result).toHaveLength(2);
+    expect(results.result[0].kind).toBe("read");
+    expect(results.result[1]).toEqual({
+        kind: "error",
+        data: { kind: "OwnerHasNoVault", data: user.address },
+    });
+
+    // Actions that must run at the top level can't be batched
+    const getVault = { function: "getVault", owner: user2.address } as const;
+    expectError(
+        await lockView({
+            function: "batchV2",
+            actions: [{ function: "batch", actions: [getVault] }],
+        }),
+        { kind: "ForbiddenNestedBatch" },
+    );
+    expectError(await lockView({ function: "batchV2", actions: [{ function: "initialize" }] }), {
+        kind: "ForbiddenActionInBatch",
+    });
+});
+
 function transferToVault(
     input: Lock.Actions["transferLocked"],
     {
@@ -235,6 +235,51 @@ it("fail if already initialized", async () => {
     });
 });
 
+it("should reject royalties that don't sum up to UNIT in the initial state", async () => {
+    const initState: Scarcity.Parameters = {
+        name: "TEST-SCARCITY-INVALID",
+        canEvolve: false,
+        initialState: {
+            settings: {
+                superOperators: [op.address],
+                operators: [],
+                erc1155: erc1155TxId,
+                custodian: op.address,
+                paused: false,
+            },
+            attachedRoyalties: {
+                VALID: {
+                    baseId: "VALID",
+                    royalties: { [op.address]: UNIT / 2, [user.address]: UNIT / 2 },
+                    rate: nftRate,
+                },
+                INVALID: {
+                    baseId: "INVALID",
+                    royalties: { [op.address]: UNIT / 2 },
+                    rate: nftRate,
+                },
+            },
+        },
+    };
+
+    const invalidContract = warp
+        .contract<Scarcity.Parameters>(
+            (await deployContract(warp, op.jwk, "scarcity", initState)).contractTxId,
+        )
+        .setEvaluationOptions({ internalWrites: true, throwOnInternalWriteError: false })
+        .connect(op.jwk);
+    const invalidInteract = createInteractor<Scarcity.Action, Scarcity.ContractError>(
+        warp,
+        invalidContract,
+        op.jwk,
+    );
+
+    expectError(await invalidInteract({ function: "initialize" }), {
+        kind: "InvalidInitialState",
+        data: [{ kind: "InvalidRoyalties", data: "INVALID" }],
+    });
+});
+
 it("activate the Scarcity contract as a proxy of Erc1155", async () => {
     expectOk(
         await erc1155Interact({
@@ -242,6 +287,13 @@ it("activate the Scarcity contract as a proxy of Erc1155", async () => {
             proxies: [scarcityTxId],
         }),
     );
+    expectOk(
+        await erc1155Interact({
+            function: "setProxyPermissions",
+            proxy: scarcityTxId,
+            permissions: ["transfer", "mint"],
+        }),
+    );
 
     const settings = await erc1155View({ function: "readSettings" });
     expectOk(settings);
@@ -380,7 +432,7 @@ it("fail to transfer an NFT that has an edition larger than its scarcity allows"
 it("mint a Limited NFT using Scarcity, mint extra NFTs with Erc1155, and transfer some", async () => {
     const mint = await scarcityInteract({
         function: "mintNft",
-        scarcity: { scarcity: "limited" },
+        scarcity: { scarcity: "limited", amount: 1 },
         royalties: { [op.address]: 1_000_000 },
         rate: 1_000_000,
     });
@@ -505,6 +557,96 @@ it("attach fees to an NFT", async () => {
     expect(royalties.result[1]).toEqual(fees);
 });
 
+it("should simulate removing royalties without removing them", async () => {
+    const removeRoyalties = { function: "removeAttachedRoyalties", baseId: nft1BaseId } as const;
+    const royalties = await scarcityView({ function: "getRoyalties", baseId: nft1BaseId });
+    expectOk(royalties);
+
+    const simulation = await scarcityView({ function: "simulate", action: removeRoyalties });
+    expectOk(simulation);
+    expect(simulation.result.error).toBeNull();
+    expect(simulation.result.changes).toEqual([
+        {
+            key: `.all_attached_royalties.${nft1BaseId}`,
+            before: {
+                base_id: nft1BaseId,
+                royalties: royalties.result[1].royalties,
+                rate: royalties.result[1].rate,
+                minter: null,
+                payment_token: null,
+            },
+            after: null,
+        },
+    ]);
+
+    const royaltiesAfter = await scarcityView({ function: "getRoyalties", baseId: nft1BaseId });
+    expectOk(royaltiesAfter);
+    expect(royaltiesAfter.result).toEqual(royalties.result);
+
+    // The wrapped action is still subject to the operators check
+    const unauthorized = await warp
+        .contract<Scarcity.Parameters>(scarcityTxId)
+        .setEvaluationOptions({ internalWrites: true, throwOnInternalWriteError: false })
+        .connect(user.jwk)
+        .viewState({ function: "simulate", action: removeRoyalties });
+    expect(unauthorized.result).toEqual({
+        simulate: {
+            error: { kind: "UnauthorizedAddress", data: user.address },
+            changes: [],
+            foreignWrites: [],
+            response: null,
+        },
+    });
+
+    // The writes to the ERC1155 contract are only reported, and assumed to succeed
+    const transfer = {
+        function: "transfer",
+        from: op.address,
+        target: user.address,
+        tokenId: nft1Id,
+        price: "0",
+    } as const;
+    const simulation = await scarcityView({ function: "simulate", action: transfer });
+    expectOk(simulation);
+    expect(simulation.result.error).toBeNull();
+    expect(simulation.result.foreignWrites.length).toBeGreaterThan(0);
+    expect(simulation.result.foreignWrites[0].contractId).toBe(erc1155TxId);
+});
+
+it("should fail an atomic batch as a whole", async () => {
+    const unknownBaseId = "UNKNOWN";
+
+    expectError(
+        await scarcityInteract({
+            function: "batchV2",
+            actions: [
+                { function: "removeAttachedRoyalties", baseId: nft1BaseId },
+                { function: "removeAttachedRoyalties", baseId: unknownBaseId },
+            ],
+        }),
+        {
+            kind: "BatchActionFailed",
+            data: { index: 1, error: { kind: "RoyaltiesNotFound", data: unknownBaseId } },
+        },
+    );
+
+    expectOk(await scarcityView({ function: "getRoyalties", baseId: nft1BaseId }));
+
+    // Actions that must run at the top level can't be batched
+    const getRoyalties = { function: "getRoyalties", baseId: nft1BaseId } as const;
+    expectError(
+        await scarcityInteract({
+            function: "batchV2",
+            actions: [{ function: "batchV2", actions: [getRoyalties] }],
+        }),
+        { kind: "ForbiddenNestedBatch" },
+    );
+    expectError(
+        await scarcityInteract({ function: "batchV2", actions: [{ function: "initialize" }] }),
+        { kind: "ForbiddenActionInBatch" },
+    );
+});
+
 it("return correct error type on bad tranfer", async () => {
     const result = await scarcityInteract({
         function: "transfer",
@@ -767,3 +909,1085 @@ it("should mint an nft, sell it and pay shareholders", async () => {
         );
     }
 }, 50_000);
+
+it("should split prices beyond f32 precision exactly and give the dust to the seller", async () => {
+    const randomId = Math.random().toString(36).substring(7);
+    const shares = [`${randomId}-1`, `${randomId}-2`, `${randomId}-3`];
+    const price = 100_000_001n;
+    const buyer = await generateWallet();
+
+    await warp.testing.addFunds(buyer.jwk);
+
+    expectOk(
+        await erc1155Interact(
+            {
+                function: "setApprovalForAll",
+                operator: op.address,
+                approved: true,
+            },
+            { wallet: buyer.jwk },
+        ),
+    );
+
+    expectOk(
+        await erc1155Interact(
+            {
+                function: "transfer",
+                from: bank.address,
+                target: buyer.address,
+                qty: price.toString(),
+            },
+            { wallet: bank.jwk },
+        ),
+    );
+
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: {
+            [shares[0]]: 333_333,
+            [shares[1]]: 333_333,
+            [shares[2]]: 333_334,
+        },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    const opBalance = await erc1155View({ function: "balanceOf", target: op.address });
+    expectOk(opBalance);
+
+    expectOk(
+        await scarcityInteract({
+            function: "transfer",
+            tokenId: nftId,
+            target: buyer.address,
+            from: op.address,
+            price: price.toString(),
+        }),
+    );
+
+    const tokensRaw = await erc1155View({ function: "getAllTokens" });
+    expectOk(tokensRaw);
+    const tokens = new Map(tokensRaw.result);
+    expect(tokens.get("DOL")?.balances[buyer.address]).toBeUndefined();
+    expect(tokens.get("DOL")?.balances[shares[0]]).toEqual("33333300");
+    expect(tokens.get("DOL")?.balances[shares[1]]).toEqual("33333300");
+    expect(tokens.get("DOL")?.balances[shares[2]]).toEqual("33333400");
+    expect(tokens.get("DOL")?.balances[op.address]).toEqual(
+        (BigInt(opBalance.result.balance) + 1n).toString(),
+    );
+}, 50_000);
+
+it("should quote sells and resells without executing them", async () => {
+    const share1 = `${Math.random().toString(36).substring(7)}-1`;
+
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [share1]: UNIT },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    const sell = await scarcityView({
+        function: "quoteTransfer",
+        from: op.address,
+        target: user.address,
+        tokenId: nftId,
+        price: "100",
+    });
+    expectOk(sell);
+    expect(sell.result).toEqual({
+        isResell: false,
+        paymentToken: null,
+        payouts: [{ address: share1, amount: "100", role: "shareholder" }],
+        tokenMovement: {
+            from: op.address,
+            target: user.address,
+            tokenId: nftId,
+            qty: "1",
+            minted: false,
+        },
+    });
+
+    {
+        const tokensRaw = await erc1155View({ function: "getAllTokens" });
+        expectOk(tokensRaw);
+        const tokens = new Map(tokensRaw.result);
+        expect(tokens.get(nftId)?.balances[op.address]).toEqual("1");
+        expect(tokens.get("DOL")?.balances[share1]).toBeUndefined();
+    }
+
+    expectOk(
+        await scarcityInteract({
+            function: "transfer",
+            from: op.address,
+            target: user.address,
+            tokenId: nftId,
+            price: "100",
+        }),
+    );
+
+    const resell = await scarcityView({
+        function: "quoteTransfer",
+        from: user.address,
+        target: bank.address,
+        tokenId: nftId,
+        price: "100",
+    });
+    expectOk(resell);
+    expect(resell.result).toEqual({
+        isResell: true,
+        paymentToken: null,
+        payouts: [
+            { address: user.address, amount: "90", role: "seller" },
+            { address: share1, amount: "10", role: "shareholder" },
+        ],
+        tokenMovement: {
+            from: user.address,
+            target: bank.address,
+            tokenId: nftId,
+            qty: "1",
+            minted: false,
+        },
+    });
+});
+
+it("should let artists mint their own NFTs and track their first sale", async () => {
+    const artist = await generateWallet();
+    const buyer = await generateWallet();
+    const rebuyer = await generateWallet();
+    const share1 = `${Math.random().toString(36).substring(7)}-1`;
+    const price = 100;
+
+    await warp.testing.addFunds(artist.jwk);
+    await warp.testing.addFunds(buyer.jwk);
+
+    const mintNft = {
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [share1]: UNIT },
+        rate: nftRate,
+    } as const;
+
+    expectError(await scarcityInteract(mintNft, { wallet: artist.jwk }), {
+        kind: "Erc1155Error",
+        data: {
+            kind: "ContractError",
+            data: { kind: "UnauthorizedAddress", data: artist.address },
+        },
+    });
+
+    expectOk(
+        await erc1155Interact({ function: "grantRole", role: "minter", account: artist.address }),
+    );
+
+    const mint = await scarcityInteract(mintNft, { wallet: artist.jwk });
+    expectOk(mint);
+    const nftBaseId = mint.originalTxId;
+    const nftId = `1-UNIQUE-${nftBaseId}`;
+
+    const royalties = await scarcityView({ function: "getRoyalties", baseId: nftBaseId });
+    expectOk(royalties);
+    expect(royalties.result[1].minter).toEqual(artist.address);
+
+    const nftBalance = await erc1155View({
+        function: "balanceOf",
+        target: artist.address,
+        tokenId: nftId,
+    });
+    expectOk(nftBalance);
+    expect(nftBalance.result.balance).toEqual("1");
+
+    // Minting under an existing base id would replace its royalties
+    expectError(
+        await scarcityInteract(
+            { ...mintNft, scarcity: { scarcity: "legendary" }, baseId: nftBaseId },
+            { wallet: artist.jwk },
+        ),
+        { kind: "TokenAlreadyExists", data: nftBaseId },
+    );
+
+    for (const wallet of [artist, buyer]) {
+        expectOk(
+            await erc1155Interact(
+                { function: "setApprovalForAll", operator: op.address, approved: true },
+                { wallet: wallet.jwk },
+            ),
+        );
+    }
+
+    expectOk(
+        await erc1155Interact(
+            {
+                function: "transfer",
+                from: bank.address,
+                target: buyer.address,
+                qty: price.toString(),
+            },
+            { wallet: bank.jwk },
+        ),
+    );
+
+    const transfer = {
+        function: "transfer",
+        from: artist.address,
+        target: buyer.address,
+        tokenId: nftId,
+        price: price.toString(),
+    } as const;
+
+    const sell = await scarcityView({ ...transfer, function: "quoteTransfer" });
+    expectOk(sell);
+    expect(sell.result.isResell).toBe(false);
+
+    expectOk(await scarcityInteract(transfer));
+
+    const resell = await scarcityView({
+        ...transfer,
+        function: "quoteTransfer",
+        from: buyer.address,
+        target: rebuyer.address,
+    });
+    expectOk(resell);
+    expect(resell.result.isResell).toBe(true);
+
+    const tokensRaw = await erc1155View({ function: "getAllTokens" });
+    expectOk(tokensRaw);
+    const tokens = new Map(tokensRaw.result);
+    expect(tokens.get(nftId)?.balances[buyer.address]).toEqual("1");
+    expect(tokens.get("DOL")?.balances[share1]).toEqual(price.toString());
+}, 50_000);
+
+it("super-operators should configure the scarcity tiers", async () => {
+    const scarcityTiers = [
+        { name: "UNIQUE", editions: 1, maxEditions: 1 },
+        { name: "MYTHIC", editions: 5, maxEditions: 5 },
+        { name: "LEGENDARY", editions: 10, maxEditions: 10 },
+        { name: "EPIC", editions: 100, maxEditions: 100 },
+        { name: "RARE", editions: 1000, maxEditions: 1000 },
+        { name: "LIMITED", editions: 1 },
+    ];
+
+    expectError(
+        await scarcityInteract({
+            function: "configure",
+            scarcityTiers: [...scarcityTiers, { name: "MY-THIC", editions: 5 }],
+        }),
+        { kind: "InvalidScarcityTier", data: "MY-THIC" },
+    );
+
+    expectError(
+        await scarcityInteract({ function: "configure", scarcityTiers: scarcityTiers.slice(1) }),
+        { kind: "ScarcityTierRemoved", data: "UNIQUE" },
+    );
+
+    expectOk(await scarcityInteract({ function: "configure", scarcityTiers }));
+
+    const mintNft = {
+        function: "mintNft",
+        scarcity: { scarcity: "mythic" },
+        royalties: { [op.address]: UNIT },
+        rate: nftRate,
+    } as const;
+
+    expectError(
+        await scarcityInteract({ ...mintNft, scarcity: { scarcity: "mythic", amount: 6 } }),
+        { kind: "InvalidEditionsCount", data: 6 },
+    );
+    expectError(await scarcityInteract({ ...mintNft, scarcity: { scarcity: "divine" } }), {
+        kind: "UnknownScarcity",
+        data: "divine",
+    });
+
+    const mint = await scarcityInteract(mintNft);
+    expectOk(mint);
+    const nftBaseId = mint.originalTxId;
+
+    for (let i = 0; i < 5; i++) {
+        expectOk(
+            await erc1155View({ function: "getToken", tokenId: `${i + 1}-MYTHIC-${nftBaseId}` }),
+        );
+    }
+    expectError(await erc1155View({ function: "getToken", tokenId: `6-MYTHIC-${nftBaseId}` }));
+
+    const quote = await scarcityView({
+        function: "quoteTransfer",
+        from: op.address,
+        target: user.address,
+        tokenId: `5-MYTHIC-${nftBaseId}`,
+        price: "0",
+    });
+    expectOk(quote);
+    expect(quote.result.isResell).toBe(false);
+}, 20_000);
+
+it("should mint the editions of lazy drops when they are first sold", async () => {
+    const artist = await generateWallet();
+    const buyer = await generateWallet();
+    const share1 = `${Math.random().toString(36).substring(7)}-1`;
+    const price = 50;
+
+    await warp.testing.addFunds(artist.jwk);
+    await warp.testing.addFunds(buyer.jwk);
+
+    const mintNft = {
+        function: "mintNft",
+        scarcity: { scarcity: "limited" },
+        royalties: { [share1]: UNIT },
+        rate: nftRate,
+        lazy: true,
+    } as const;
+
+    // Lazy drops bypass the minter role of the ERC1155 contract
+    expectError(
+        await scarcityInteract(
+            { ...mintNft, scarcity: { scarcity: "limited", amount: 10 } },
+            { wallet: artist.jwk },
+        ),
+        { kind: "UnauthorizedAddress", data: artist.address },
+    );
+    expectOk(await scarcityInteract({ function: "configure", operators: [artist.address] }));
+
+    expectError(await scarcityInteract(mintNft, { wallet: artist.jwk }), {
+        kind: "EditionsCountRequired",
+        data: "LIMITED",
+    });
+
+    const mint = await scarcityInteract(
+        { ...mintNft, scarcity: { scarcity: "limited", amount: 5000 } },
+        { wallet: artist.jwk },
+    );
+    expectOk(mint);
+    const nftBaseId = mint.originalTxId;
+    const nftId = `42-LIMITED-${nftBaseId}`;
+
+    expectError(await erc1155View({ function: "getToken", tokenId: nftId }));
+
+    expectOk(
+        await erc1155Interact(
+            { function: "setApprovalForAll", operator: op.address, approved: true },
+            { wallet: buyer.jwk },
+        ),
+    );
+    expectOk(
+        await erc1155Interact(
+            {
+                function: "transfer",
+                from: bank.address,
+                target: buyer.address,
+                qty: price.toString(),
+            },
+            { wallet: bank.jwk },
+        ),
+    );
+
+    const transfer = {
+        function: "transfer",
+        from: artist.address,
+        target: buyer.address,
+        tokenId: nftId,
+        price: price.toString(),
+    } as const;
+
+    expectError(await scarcityInteract({ ...transfer, tokenId: `5001-LIMITED-${nftBaseId}` }), {
+        kind: "TokenDoesNotExist",
+        data: `5001-LIMITED-${nftBaseId}`,
+    });
+    expectError(await scarcityInteract({ ...transfer, from: user.address }), {
+        kind: "UnauthorizedTransfer",
+        data: user.address,
+    });
+
+    const quote = await scarcityView({ ...transfer, function: "quoteTransfer" });
+    expectOk(quote);
+    expect(quote.result.isResell).toBe(false);
+    expect(quote.result.tokenMovement.minted).toBe(true);
+
+    expectOk(await scarcityInteract(transfer));
+
+    const nftBalance = await erc1155View({
+        function: "balanceOf",
+        target: buyer.address,
+        tokenId: nftId,
+    });
+    expectOk(nftBalance);
+    expect(nftBalance.result.balance).toEqual("1");
+
+    const lazyDrop = await scarcityView({ function: "getLazyDrop", baseId: nftBaseId });
+    expectOk(lazyDrop);
+    expect(lazyDrop.result).toEqual({
+        baseId: nftBaseId,
+        scarcity: "LIMITED",
+        editions: 5000,
+        minted: 1,
+    });
+
+    // The edition now exists and can only be resold by its owner
+    const resell = await scarcityView({
+        ...transfer,
+        function: "quoteTransfer",
+        from: buyer.address,
+        target: user.address,
+    });
+    expectOk(resell);
+    expect(resell.result.isResell).toBe(true);
+    expect(resell.result.tokenMovement.minted).toBe(false);
+
+    expectOk(await scarcityInteract({ function: "configure", operators: [] }));
+}, 50_000);
+
+it("should record the provenance of the NFTs sold through transfer", async () => {
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [op.address]: UNIT },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    const noSale = await scarcityView({ function: "getLastSale", tokenId: nftId });
+    expectOk(noSale);
+    expect(noSale.result).toBeNull();
+
+    const sell = await scarcityInteract({
+        function: "transfer",
+        from: op.address,
+        target: user.address,
+        tokenId: nftId,
+        price: "0",
+    });
+    expectOk(sell);
+    const resell = await scarcityInteract({
+        function: "transfer",
+        from: user.address,
+        target: op.address,
+        tokenId: nftId,
+        price: "0",
+    });
+    expectOk(resell);
+
+    const lastSale = await scarcityView({ function: "getLastSale", tokenId: nftId });
+    expectOk(lastSale);
+    expect(lastSale.result).toMatchObject({
+        from: user.address,
+        to: op.address,
+        qty: "1",
+        price: "0",
+        txId: resell.originalTxId,
+        kind: "secondary",
+    });
+
+    const firstPage = await scarcityView({
+        function: "getSalesHistory",
+        tokenId: nftId,
+        limit: 1,
+    });
+    expectOk(firstPage);
+    expect(firstPage.result.salesCount).toEqual(2);
+    expect(firstPage.result.next).toEqual(0);
+    expect(firstPage.result.sales).toMatchObject([
+        [0, { from: op.address, to: user.address, txId: sell.originalTxId, kind: "primary" }],
+    ]);
+
+    const secondPage = await scarcityView({
+        function: "getSalesHistory",
+        tokenId: nftId,
+        after: firstPage.result.next!,
+    });
+    expectOk(secondPage);
+    expect(secondPage.result.next).toBeNull();
+    expect(secondPage.result.sales.map(([index]) => index)).toEqual([1]);
+
+    for (const limit of [0, 1001]) {
+        expectError(await scarcityView({ function: "getSalesHistory", tokenId: nftId, limit }), {
+            kind: "InvalidLimit",
+            data: limit,
+        });
+    }
+}, 20_000);
+
+it("should let owners list their NFTs and anyone buy them", async () => {
+    const seller = await generateWallet();
+    const buyer = await generateWallet();
+    const price = 1_000;
+
+    await warp.testing.addFunds(seller.jwk);
+    await warp.testing.addFunds(buyer.jwk);
+
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [op.address]: UNIT },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    expectOk(
+        await scarcityInteract({
+            function: "transfer",
+            from: op.address,
+            target: seller.address,
+            tokenId: nftId,
+            price: "0",
+        }),
+    );
+
+    // Listing and buying require the parties to approve the Scarcity contract
+    for (const wallet of [seller, buyer]) {
+        expectOk(
+            await erc1155Interact(
+                { function: "setApprovalForAll", operator: scarcityTxId, approved: true },
+                { wallet: wallet.jwk },
+            ),
+        );
+    }
+    expectOk(
+        await erc1155Interact(
+            {
+                function: "transfer",
+                from: bank.address,
+                target: buyer.address,
+                qty: price.toString(),
+            },
+            { wallet: bank.jwk },
+        ),
+    );
+
+    const list = {
+        function: "list",
+        tokenId: nftId,
+        price: price.toString(),
+        paymentToken: null,
+        expiresAt: null,
+    } as const;
+
+    // Escrowing the NFT fails for the accounts that don't own it
+    expectError(await scarcityInteract(list, { wallet: buyer.jwk }), {
+        kind: "Erc1155Error",
+        data: {
+            kind: "ContractError",
+            data: { kind: "OwnerBalanceNotEnough", data: buyer.address },
+        },
+    });
+    expectOk(await scarcityInteract(list, { wallet: seller.jwk }));
+
+    const escrow = await erc1155View({
+        function: "balanceOf",
+        target: scarcityTxId,
+        tokenId: nftId,
+    });
+    expectOk(escrow);
+    expect(escrow.result.balance).toEqual("1");
+
+    const listing = await scarcityView({ function: "getListing", tokenId: nftId });
+    expectOk(listing);
+    expect(listing.result).toEqual({
+        tokenId: nftId,
+        seller: seller.address,
+        price: price.toString(),
+        paymentToken: null,
+        expiresAt: null,
+        escrowed: true,
+    });
+
+    const sellerListings = await scarcityView({
+        function: "getSellerListings",
+        seller: seller.address,
+    });
+    expectOk(sellerListings);
+    expect(sellerListings.result.map(({ tokenId }) => tokenId)).toEqual([nftId]);
+
+    expectError(
+        await scarcityInteract({ function: "delist", tokenId: nftId }, { wallet: buyer.jwk }),
+        { kind: "UnauthorizedAddress", data: buyer.address },
+    );
+    expectError(
+        await scarcityInteract({ function: "buy", tokenId: nftId }, { wallet: seller.jwk }),
+        { kind: "TransferFromAndToCannotBeEqual" },
+    );
+
+    expectOk(await scarcityInteract({ function: "buy", tokenId: nftId }, { wallet: buyer.jwk }));
+
+    const tokensRaw = await erc1155View({ function: "getAllTokens" });
+    expectOk(tokensRaw);
+    const tokens = new Map(tokensRaw.result);
+    expect(tokens.get(nftId)?.balances[buyer.address]).toEqual("1");
+    expect(tokens.get("DOL")?.balances[seller.address]).toEqual(
+        (price - (price * nftRate) / UNIT).toString(),
+    );
+
+    const noListing = await scarcityView({ function: "getListing", tokenId: nftId });
+    expectOk(noListing);
+    expect(noListing.result).toBeNull();
+
+    const lastSale = await scarcityView({ function: "getLastSale", tokenId: nftId });
+    expectOk(lastSale);
+    expect(lastSale.result).toMatchObject({
+        from: seller.address,
+        to: buyer.address,
+        price: price.toString(),
+        kind: "secondary",
+    });
+
+    // Delisting gives the NFT back to its seller
+    expectOk(await scarcityInteract({ ...list, price: "2000" }, { wallet: buyer.jwk }));
+    expectOk(await scarcityInteract({ function: "delist", tokenId: nftId }, { wallet: buyer.jwk }));
+
+    const nftBalance = await erc1155View({
+        function: "balanceOf",
+        target: buyer.address,
+        tokenId: nftId,
+    });
+    expectOk(nftBalance);
+    expect(nftBalance.result.balance).toEqual("1");
+}, 60_000);
+
+it("should hold offers in escrow until they are accepted or cancelled", async () => {
+    const owner = await generateWallet();
+    const bidder = await generateWallet();
+    const price = 500;
+
+    await warp.testing.addFunds(owner.jwk);
+    await warp.testing.addFunds(bidder.jwk);
+
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [op.address]: UNIT },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    expectOk(
+        await scarcityInteract({
+            function: "transfer",
+            from: op.address,
+            target: owner.address,
+            tokenId: nftId,
+            price: "0",
+        }),
+    );
+
+    for (const wallet of [owner, bidder]) {
+        expectOk(
+            await erc1155Interact(
+                { function: "setApprovalForAll", operator: scarcityTxId, approved: true },
+                { wallet: wallet.jwk },
+            ),
+        );
+    }
+    expectOk(
+        await erc1155Interact(
+            {
+                function: "transfer",
+                from: bank.address,
+                target: bidder.address,
+                qty: (2 * price).toString(),
+            },
+            { wallet: bank.jwk },
+        ),
+    );
+
+    const makeOffer = {
+        function: "makeOffer",
+        tokenId: nftId,
+        price: price.toString(),
+        paymentToken: null,
+        expiresAt: null,
+    } as const;
+
+    const cancelledOffer = await scarcityInteract(makeOffer, { wallet: bidder.jwk });
+    expectOk(cancelledOffer);
+    const acceptedOffer = await scarcityInteract(makeOffer, { wallet: bidder.jwk });
+    expectOk(acceptedOffer);
+
+    const bidderOffers = await scarcityView({
+        function: "getBidderOffers",
+        bidder: bidder.address,
+    });
+    expectOk(bidderOffers);
+    expect(bidderOffers.result.map(({ id }) => id)).toEqual([
+        cancelledOffer.originalTxId,
+        acceptedOffer.originalTxId,
+    ]);
+
+    const escrow = await erc1155View({ function: "balanceOf", target: scarcityTxId });
+    expectOk(escrow);
+    expect(escrow.result.balance).toEqual((2 * price).toString());
+
+    expectError(
+        await scarcityInteract(
+            { function: "cancelOffer", offerId: cancelledOffer.originalTxId },
+            { wallet: owner.jwk },
+        ),
+        { kind: "UnauthorizedAddress", data: owner.address },
+    );
+    expectOk(
+        await scarcityInteract(
+            { function: "cancelOffer", offerId: cancelledOffer.originalTxId },
+            { wallet: bidder.jwk },
+        ),
+    );
+
+    expectOk(
+        await scarcityInteract(
+            { function: "acceptOffer", offerId: acceptedOffer.originalTxId },
+            { wallet: owner.jwk },
+        ),
+    );
+
+    const tokensRaw = await erc1155View({ function: "getAllTokens" });
+    expectOk(tokensRaw);
+    const tokens = new Map(tokensRaw.result);
+    expect(tokens.get(nftId)?.balances[bidder.address]).toEqual("1");
+    expect(tokens.get("DOL")?.balances[bidder.address]).toEqual(price.toString());
+    expect(tokens.get("DOL")?.balances[owner.address]).toEqual(
+        (price - (price * nftRate) / UNIT).toString(),
+    );
+    expect(tokens.get("DOL")?.balances[scarcityTxId]).toBeUndefined();
+
+    const tokenOffers = await scarcityView({ function: "getTokenOffers", tokenId: nftId });
+    expectOk(tokenOffers);
+    expect(tokenOffers.result).toEqual([]);
+
+    // Offers made by the same transaction don't overwrite each other
+    expectOk(
+        await erc1155Interact({
+            function: "setApprovalForAll",
+            operator: scarcityTxId,
+            approved: true,
+        }),
+    );
+    const batchedOffers = await scarcityInteract({
+        function: "batchV2",
+        actions: [makeOffer, makeOffer],
+    });
+    expectOk(batchedOffers);
+    const offerIds = [batchedOffers.originalTxId, `${batchedOffers.originalTxId}-1`];
+
+    const opOffers = await scarcityView({ function: "getBidderOffers", bidder: op.address });
+    expectOk(opOffers);
+    expect(opOffers.result.map(({ id }) => id)).toEqual(offerIds);
+
+    expectOk(
+        await scarcityInteract({
+            function: "batchV2",
+            actions: offerIds.map((offerId) => ({ function: "cancelOffer", offerId }) as const),
+        }),
+    );
+}, 60_000);
+
+it("should auction NFTs to their highest bidder", async () => {
+    const seller = await generateWallet();
+    const bidder1 = await generateWallet();
+    const bidder2 = await generateWallet();
+    const reservePrice = 100;
+
+    for (const wallet of [seller, bidder1, bidder2]) {
+        await warp.testing.addFunds(wallet.jwk);
+        expectOk(
+            await erc1155Interact(
+                { function: "setApprovalForAll", operator: scarcityTxId, approved: true },
+                { wallet: wallet.jwk },
+            ),
+        );
+    }
+    for (const wallet of [bidder1, bidder2]) {
+        expectOk(
+            await erc1155Interact(
+                { function: "transfer", from: bank.address, target: wallet.address, qty: "1000" },
+                { wallet: bank.jwk },
+            ),
+        );
+    }
+
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [op.address]: UNIT },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    expectOk(
+        await scarcityInteract({
+            function: "transfer",
+            from: op.address,
+            target: seller.address,
+            tokenId: nftId,
+            price: "0",
+        }),
+    );
+
+    const height = (await warp.arweave.network.getInfo()).height;
+    const endHeight = height + 4;
+
+    expectOk(
+        await scarcityInteract(
+            {
+                function: "createAuction",
+                tokenId: nftId,
+                reservePrice: reservePrice.toString(),
+                paymentToken: null,
+                startHeight: height,
+                endHeight,
+                minIncrement: "10",
+            },
+            { wallet: seller.jwk },
+        ),
+    );
+
+    const bid = (amount: number) =>
+        ({ function: "bid", tokenId: nftId, amount: amount.toString() }) as const;
+
+    expectError(await scarcityInteract(bid(reservePrice - 1), { wallet: bidder1.jwk }), {
+        kind: "BidTooLow",
+        data: reservePrice.toString(),
+    });
+    expectOk(await scarcityInteract(bid(reservePrice), { wallet: bidder1.jwk }));
+    expectError(await scarcityInteract(bid(reservePrice + 5), { wallet: bidder2.jwk }), {
+        kind: "BidTooLow",
+        data: (reservePrice + 10).toString(),
+    });
+    expectOk(await scarcityInteract(bid(reservePrice + 50), { wallet: bidder2.jwk }));
+
+    const auction = await scarcityView({ function: "getAuction", tokenId: nftId });
+    expectOk(auction);
+    expect(auction.result?.highestBid).toEqual({
+        bidder: bidder2.address,
+        amount: (reservePrice + 50).toString(),
+    });
+    // Late bids push the end of the auction back
+    expect(auction.result?.endHeight).toBeGreaterThan(endHeight);
+
+    expectError(await scarcityInteract({ function: "settleAuction", tokenId: nftId }), {
+        kind: "AuctionNotEnded",
+        data: nftId,
+    });
+
+    while ((await warp.arweave.network.getInfo()).height < auction.result!.endHeight) {
+        await warp.testing.mineBlock();
+    }
+
+    expectOk(await scarcityInteract({ function: "settleAuction", tokenId: nftId }));
+
+    const tokensRaw = await erc1155View({ function: "getAllTokens" });
+    expectOk(tokensRaw);
+    const tokens = new Map(tokensRaw.result);
+    expect(tokens.get(nftId)?.balances[bidder2.address]).toEqual("1");
+    expect(tokens.get("DOL")?.balances[bidder1.address]).toEqual(
+        (1000 - reservePrice).toString(),
+    );
+    expect(tokens.get("DOL")?.balances[bidder2.address]).toEqual(
+        (1000 - reservePrice - 50).toString(),
+    );
+    expect(tokens.get("DOL")?.balances[seller.address]).toEqual(
+        (((reservePrice + 50) * (UNIT - nftRate)) / UNIT).toString(),
+    );
+
+    const settled = await scarcityView({ function: "getAuction", tokenId: nftId });
+    expectOk(settled);
+    expect(settled.result).toBeNull();
+
+    // Outbid amounts are credited to their bidders, who withdraw them
+    const credits = await scarcityView({ function: "getCredits", account: bidder1.address });
+    expectOk(credits);
+    expect(credits.result).toEqual([{ paymentToken: null, amount: reservePrice.toString() }]);
+
+    expectOk(await scarcityInteract({ function: "withdraw" }, { wallet: bidder1.jwk }));
+    expectError(await scarcityInteract({ function: "withdraw" }, { wallet: bidder1.jwk }), {
+        kind: "NothingToWithdraw",
+    });
+
+    const withdrawn = await erc1155View({ function: "balanceOf", target: bidder1.address });
+    expectOk(withdrawn);
+    expect(withdrawn.result.balance).toEqual("1000");
+}, 80_000);
+
+it("should let operators cancel the auctions that can't be settled", async () => {
+    const seller = await generateWallet();
+    const bidder = await generateWallet();
+    const reservePrice = 100;
+
+    for (const wallet of [seller, bidder]) {
+        await warp.testing.addFunds(wallet.jwk);
+        expectOk(
+            await erc1155Interact(
+                { function: "setApprovalForAll", operator: scarcityTxId, approved: true },
+                { wallet: wallet.jwk },
+            ),
+        );
+    }
+    expectOk(
+        await erc1155Interact(
+            { function: "transfer", from: bank.address, target: bidder.address, qty: "1000" },
+            { wallet: bank.jwk },
+        ),
+    );
+
+    const mint = await scarcityInteract({
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [op.address]: UNIT },
+        rate: nftRate,
+    });
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    expectOk(
+        await scarcityInteract({
+            function: "transfer",
+            from: op.address,
+            target: seller.address,
+            tokenId: nftId,
+            price: "0",
+        }),
+    );
+
+    const height = (await warp.arweave.network.getInfo()).height;
+    const createAuction = {
+        function: "createAuction",
+        tokenId: nftId,
+        reservePrice: reservePrice.toString(),
+        paymentToken: null,
+        startHeight: height,
+        endHeight: height + 10,
+        minIncrement: "10",
+    } as const;
+    const cancelAuction = { function: "cancelAuction", tokenId: nftId } as const;
+
+    // Sellers may cancel their auctions until they're bid on
+    expectOk(await scarcityInteract(createAuction, { wallet: seller.jwk }));
+    expectOk(await scarcityInteract(cancelAuction, { wallet: seller.jwk }));
+
+    expectOk(await scarcityInteract(createAuction, { wallet: seller.jwk }));
+    expectOk(
+        await scarcityInteract(
+            { function: "bid", tokenId: nftId, amount: reservePrice.toString() },
+            { wallet: bidder.jwk },
+        ),
+    );
+
+    expectError(await scarcityInteract(cancelAuction, { wallet: seller.jwk }), {
+        kind: "AuctionHasBids",
+        data: nftId,
+    });
+    expectError(await scarcityInteract(cancelAuction, { wallet: user.jwk }), {
+        kind: "UnauthorizedAddress",
+        data: user.address,
+    });
+    expectOk(await scarcityInteract(cancelAuction));
+
+    const nftBalance = await erc1155View({
+        function: "balanceOf",
+        target: seller.address,
+        tokenId: nftId,
+    });
+    expectOk(nftBalance);
+    expect(nftBalance.result.balance).toEqual("1");
+
+    const credits = await scarcityView({ function: "getCredits", account: bidder.address });
+    expectOk(credits);
+    expect(credits.result).toEqual([{ paymentToken: null, amount: reservePrice.toString() }]);
+}, 80_000);
+
+it("should sell NFTs for the payment tokens allowed by the operators", async () => {
+    const share1 = `${Math.random().toString(36).substring(7)}-1`;
+    const paymentToken = `GOLD${Math.random().toString(36).substring(7)}`;
+    const price = 100;
+
+    expectOk(await erc1155Interact({ function: "mint", baseId: paymentToken, qty: "1000" }));
+    expectOk(
+        await erc1155Interact({
+            function: "transfer",
+            from: op.address,
+            target: user.address,
+            tokenId: paymentToken,
+            qty: "1000",
+        }),
+    );
+
+    const mintNft = {
+        function: "mintNft",
+        scarcity: { scarcity: "unique" },
+        royalties: { [share1]: UNIT },
+        rate: nftRate,
+        paymentToken,
+    } as const;
+
+    expectError(await scarcityInteract(mintNft), {
+        kind: "PaymentTokenNotAllowed",
+        data: paymentToken,
+    });
+
+    expectOk(await scarcityInteract({ function: "configure", paymentTokens: [paymentToken] }));
+
+    const mint = await scarcityInteract(mintNft);
+    expectOk(mint);
+    const nftId = `1-UNIQUE-${mint.originalTxId}`;
+
+    const transfer = {
+        function: "transfer",
+        from: op.address,
+        target: user.address,
+        tokenId: nftId,
+        price: price.toString(),
+    } as const;
+
+    expectError(await scarcityInteract({ ...transfer, paymentToken: "SILVER" }), {
+        kind: "PaymentTokenNotAllowed",
+        data: "SILVER",
+    });
+
+    // The NFT is sold for the payment token of its drop unless the sale specifies one
+    const quote = await scarcityView({ ...transfer, function: "quoteTransfer" });
+    expectOk(quote);
+    expect(quote.result.paymentToken).toEqual(paymentToken);
+
+    expectOk(await scarcityInteract(transfer));
+
+    const tokensRaw = await erc1155View({ function: "getAllTokens" });
+    expectOk(tokensRaw);
+    const tokens = new Map(tokensRaw.result);
+    expect(tokens.get(nftId)?.balances[user.address]).toEqual("1");
+    expect(tokens.get(paymentToken)?.balances[share1]).toEqual(price.toString());
+    expect(tokens.get(paymentToken)?.balances[user.address]).toEqual((1000 - price).toString());
+    expect(tokens.get("DOL")?.balances[share1]).toBeUndefined();
+
+    // Existing listings can't be bought anymore once their token is removed
+    expectOk(
+        await erc1155Interact(
+            { function: "setApprovalForAll", operator: scarcityTxId, approved: true },
+            { wallet: user.jwk },
+        ),
+    );
+    expectOk(
+        await scarcityInteract(
+            {
+                function: "list",
+                tokenId: nftId,
+                price: price.toString(),
+                paymentToken: null,
+                expiresAt: null,
+            },
+            { wallet: user.jwk },
+        ),
+    );
+
+    expectOk(await scarcityInteract({ function: "configure", paymentTokens: [] }));
+
+    expectError(await scarcityInteract({ function: "buy", tokenId: nftId }), {
+        kind: "PaymentTokenNotAllowed",
+        data: paymentToken,
+    });
+    expectOk(
+        await scarcityInteract({ function: "delist", tokenId: nftId }, { wallet: user.jwk }),
+    );
+}, 40_000);